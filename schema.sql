@@ -16,18 +16,108 @@ CREATE TABLE "public"."users" (
     "phone_number" character varying NOT NULL,
     "role" character varying,
     "referrer" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
-    "stripe_customer_id" character varying
+    "stripe_customer_id" character varying,
+    "email_verified_at" timestamp with time zone
 );
 
 CREATE UNIQUE INDEX index_users_email ON public.users USING btree (email);
 
+CREATE TABLE "public"."application_forms" (
+    "application_type" character varying NOT NULL,
+    "version" integer NOT NULL,
+    "title" character varying NOT NULL,
+    "description" character varying,
+    "schema" jsonb NOT NULL,
+    "created_by" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY ("application_type", "version")
+);
+
 CREATE TABLE "public"."applications" (
     "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
     "created_at" timestamp with time zone NOT NULL DEFAULT now(),
     "application" jsonb NOT NULL,
-    "application_type" character varying NOT NULL
+    "application_type" character varying NOT NULL,
+    "form_version" integer,
+    FOREIGN KEY ("application_type", "form_version") REFERENCES "public"."application_forms" ("application_type", "version") ON UPDATE NO ACTION ON DELETE RESTRICT
+);
+
+CREATE INDEX index_applications_user_id ON public.applications USING btree (user_id);
+
+CREATE UNIQUE INDEX index_applications_open_citizenship ON public.applications USING btree (user_id) WHERE ((application_type)::text = 'citizenship'::text AND (application ->> 'citizenship_status'::text) = ANY (ARRAY['Pending'::text, 'InformationRequested'::text]));
+
+CREATE TABLE "public"."application_reviews" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "application_id" uuid NOT NULL REFERENCES "public"."applications" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "reviewer_id" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "status" character varying NOT NULL,
+    "reason" character varying,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_application_reviews_application_id ON public.application_reviews USING btree (application_id);
+
+CREATE TABLE "public"."application_events" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "application_id" uuid NOT NULL REFERENCES "public"."applications" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "kind" character varying NOT NULL,
+    "actor_id" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "status" character varying,
+    "message" character varying,
+    "internal" boolean NOT NULL DEFAULT false,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_application_events_application_id ON public.application_events USING btree (application_id);
+
+CREATE TABLE "public"."application_documents" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "application_id" uuid NOT NULL REFERENCES "public"."applications" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "file_name" character varying NOT NULL,
+    "content_type" character varying NOT NULL,
+    "size" bigint NOT NULL,
+    "storage_key" character varying NOT NULL,
+    "uploaded_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_application_documents_application_id ON public.application_documents USING btree (application_id);
+
+CREATE TABLE "public"."application_drafts" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "application_type" character varying NOT NULL,
+    "draft" jsonb NOT NULL,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now(),
+    "updated_at" timestamp with time zone NOT NULL DEFAULT now(),
+    "expires_at" timestamp with time zone NOT NULL
+);
+
+CREATE INDEX index_application_drafts_user_id ON public.application_drafts USING btree (user_id);
+
+CREATE TABLE "public"."citizens" (
+    "user_id" uuid PRIMARY KEY NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "number" bigint GENERATED BY DEFAULT AS IDENTITY NOT NULL UNIQUE,
+    "application_id" uuid REFERENCES "public"."applications" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "status" character varying NOT NULL,
+    "status_reason" character varying,
+    "granted_at" timestamp with time zone NOT NULL,
+    "updated_at" timestamp with time zone NOT NULL
+);
+
+CREATE TABLE "public"."citizenship_credentials" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "issued_at" timestamp with time zone NOT NULL,
+    "expires_at" timestamp with time zone NOT NULL,
+    "revoked_at" timestamp with time zone,
+    "revocation_reason" character varying
 );
 
+CREATE INDEX index_citizenship_credentials_user_id ON public.citizenship_credentials USING btree (user_id);
+
+CREATE INDEX index_citizenship_credentials_revoked_at ON public.citizenship_credentials USING btree (revoked_at) WHERE (revoked_at IS NOT NULL);
+
 CREATE TABLE "public"."oauth_apps" (
     "client_id" character varying PRIMARY KEY NOT NULL,
     "redirect_uris" character varying NOT NULL,
@@ -43,6 +133,57 @@ CREATE TABLE "public"."oauth_grants" (
     PRIMARY KEY ("user_id", "client_id")
 );
 
+CREATE TABLE "public"."courses" (
+    "slug" character varying PRIMARY KEY NOT NULL,
+    "title" character varying NOT NULL,
+    "description" character varying,
+    "position" integer NOT NULL DEFAULT 0,
+    "published_at" timestamp with time zone,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now(),
+    "updated_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE TABLE "public"."course_prerequisites" (
+    "course_slug" character varying NOT NULL REFERENCES "public"."courses" ("slug") ON UPDATE CASCADE ON DELETE CASCADE,
+    "prerequisite_slug" character varying NOT NULL REFERENCES "public"."courses" ("slug") ON UPDATE CASCADE ON DELETE CASCADE,
+    PRIMARY KEY ("course_slug", "prerequisite_slug"),
+    CHECK ("course_slug" <> "prerequisite_slug")
+);
+
+CREATE TABLE "public"."course_units" (
+    "course_slug" character varying NOT NULL REFERENCES "public"."courses" ("slug") ON UPDATE CASCADE ON DELETE CASCADE,
+    "slug" character varying NOT NULL,
+    "title" character varying NOT NULL,
+    "description" character varying,
+    "position" integer NOT NULL DEFAULT 0,
+    PRIMARY KEY ("course_slug", "slug")
+);
+
+CREATE TABLE "public"."unit_prerequisites" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "course_slug" character varying NOT NULL,
+    "unit_slug" character varying NOT NULL,
+    "rule" character varying NOT NULL,
+    "prerequisite_unit_slug" character varying,
+    FOREIGN KEY ("course_slug", "unit_slug") REFERENCES "public"."course_units" ("course_slug", "slug") ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY ("course_slug", "prerequisite_unit_slug") REFERENCES "public"."course_units" ("course_slug", "slug") ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX index_unit_prerequisites_course_unit ON public.unit_prerequisites USING btree (course_slug, unit_slug);
+
+CREATE TABLE "public"."unit_questions" (
+    "course_slug" character varying NOT NULL,
+    "unit_slug" character varying NOT NULL,
+    "slug" character varying NOT NULL,
+    "question" character varying NOT NULL,
+    "context" character varying,
+    "reference_answer" character varying,
+    "rubric" jsonb,
+    "position" integer NOT NULL DEFAULT 0,
+    PRIMARY KEY ("course_slug", "unit_slug", "slug"),
+    FOREIGN KEY ("course_slug", "unit_slug") REFERENCES "public"."course_units" ("course_slug", "slug") ON UPDATE CASCADE ON DELETE CASCADE
+);
+
 CREATE TABLE "public"."question_assessments" (
     "id" uuid PRIMARY KEY NOT NULL,
     "user_id" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
@@ -52,11 +193,105 @@ CREATE TABLE "public"."question_assessments" (
     "answer" character varying NOT NULL,
     "assessment" assessment NOT NULL,
     "feedback" character varying NOT NULL,
+    "rubric_scores" jsonb,
+    "attempt" integer NOT NULL DEFAULT 1,
+    "flagged_at" timestamp with time zone,
+    "flag_reason" character varying,
+    "reviewed_by" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "reviewed_at" timestamp with time zone,
+    "prompt_version" integer,
     "updated_at" timestamp with time zone NOT NULL DEFAULT now()
 );
 
 CREATE UNIQUE INDEX index_assessment_questions_user_course_unit_question ON public.question_assessments USING btree (user_id, course_slug, unit_slug, question_slug);
 
+CREATE TABLE "public"."question_assessment_overrides" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "question_assessment_id" uuid NOT NULL REFERENCES "public"."question_assessments" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "attempt" integer NOT NULL,
+    "reviewer_id" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "previous_assessment" assessment NOT NULL,
+    "previous_feedback" character varying NOT NULL,
+    "assessment" assessment NOT NULL,
+    "feedback" character varying NOT NULL,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_question_assessment_overrides_question_assessment_id ON public.question_assessment_overrides USING btree (question_assessment_id);
+
+CREATE TABLE "public"."question_attempts" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "course_slug" character varying NOT NULL,
+    "unit_slug" character varying NOT NULL,
+    "question_slug" character varying NOT NULL,
+    "attempt" integer NOT NULL,
+    "answer" character varying NOT NULL,
+    "assessment" assessment NOT NULL,
+    "feedback" character varying NOT NULL,
+    "rubric_scores" jsonb,
+    "answer_hash" character varying,
+    "prompt_version" integer,
+    "cached" boolean NOT NULL DEFAULT false,
+    "reviewed_by" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "reviewed_at" timestamp with time zone,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now(),
+    FOREIGN KEY ("course_slug", "unit_slug", "question_slug") REFERENCES "public"."unit_questions" ("course_slug", "unit_slug", "slug") ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX index_question_attempts_user_question_attempt ON public.question_attempts USING btree (user_id, course_slug, unit_slug, question_slug, attempt);
+
+CREATE INDEX index_question_attempts_answer_hash_created_at ON public.question_attempts USING btree (answer_hash, created_at);
+
+CREATE TABLE "public"."tutor_threads" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "question_assessment_id" uuid NOT NULL REFERENCES "public"."question_assessments" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "attempt" integer NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now(),
+    "updated_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_tutor_threads_question_assessment_attempt ON public.tutor_threads USING btree (question_assessment_id, attempt);
+
+CREATE TABLE "public"."tutor_messages" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "thread_id" uuid NOT NULL REFERENCES "public"."tutor_threads" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "role" character varying NOT NULL,
+    "content" character varying NOT NULL,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_tutor_messages_thread_id_created_at ON public.tutor_messages USING btree (thread_id, created_at);
+
+CREATE TABLE "public"."ai_usage" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+    "feature" character varying NOT NULL,
+    "model" character varying NOT NULL,
+    "prompt_tokens" integer NOT NULL,
+    "completion_tokens" integer NOT NULL,
+    "cost_micro_usd" bigint NOT NULL,
+    "created_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_ai_usage_user_id_created_at ON public.ai_usage USING btree (user_id, created_at);
+
+CREATE INDEX index_ai_usage_created_at ON public.ai_usage USING btree (created_at);
+
+CREATE TABLE "public"."ai_daily_requests" (
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "day" date NOT NULL,
+    "requests" integer NOT NULL,
+    PRIMARY KEY ("user_id", "day")
+);
+
+CREATE TABLE "public"."ai_quotas" (
+    "user_id" uuid PRIMARY KEY NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "daily_requests" integer NOT NULL,
+    "updated_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
 CREATE TABLE "public"."unit_progress" (
     "id" uuid PRIMARY KEY NOT NULL,
     "user_id" uuid REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
@@ -68,8 +303,26 @@ CREATE TABLE "public"."unit_progress" (
 
 ALTER TABLE "public"."unit_progress" ADD CONSTRAINT "unique_user_unit_course" UNIQUE (user_id, unit_slug, course_slug);
 
+CREATE TABLE "public"."course_certificates" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+    "course_slug" character varying NOT NULL REFERENCES "public"."courses" ("slug") ON UPDATE CASCADE ON DELETE RESTRICT,
+    "learner_name" character varying NOT NULL,
+    "course_title" character varying NOT NULL,
+    "completed_at" timestamp with time zone NOT NULL,
+    "issued_at" timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_course_certificates_user_course ON public.course_certificates USING btree (user_id, course_slug);
+
 CREATE TABLE "public"."password_reset_tokens" (
     "id" uuid PRIMARY KEY NOT NULL,
     "user_id" uuid NOT NULL REFERENCES "public"."users"(id) ON DELETE CASCADE,
     "expires_at" timestamp with time zone NOT NULL
 );
+
+CREATE TABLE "public"."email_verification_tokens" (
+    "id" uuid PRIMARY KEY NOT NULL,
+    "user_id" uuid NOT NULL REFERENCES "public"."users"(id) ON DELETE CASCADE,
+    "expires_at" timestamp with time zone NOT NULL
+);
@@ -0,0 +1,270 @@
+//! Accounting for requests to AI models, which are paid for per token.
+//!
+//! Every request is recorded with the tokens it used and what it cost, and
+//! users can only make so many requests a day (UTC). Users with a Light
+//! University subscription get a bigger quota, and admins can give any user
+//! a quota of their own.
+//!
+//! A request is reserved against the quota before the model is called, and
+//! settled with its token counts once the model replies. Requests that fail
+//! still count towards the quota.
+
+use async_graphql::Enum;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult,
+    IntoActiveModel, Set, Statement, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::new_err,
+    graphql::types::user::User,
+    schema::{ai_quotas, ai_usage},
+    util::{stripe::light_university_subscription, variables::SECRET_VARIABLES},
+};
+
+pub const QUESTION_ASSESSMENT_FEATURE: &str = "question_assessment";
+pub const TUTOR_FEATURE: &str = "tutor";
+
+/// The tokens a model request used, as reported by the model's API
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub model: String,
+    pub prompt_tokens: i32,
+    pub completion_tokens: i32,
+}
+
+impl TokenUsage {
+    /// The cost in millionths of a US dollar, given prices in US dollars per
+    /// million tokens
+    pub fn cost_micro_usd(&self, prompt_price: f64, completion_price: f64) -> i64 {
+        (f64::from(self.prompt_tokens) * prompt_price
+            + f64::from(self.completion_tokens) * completion_price)
+            .round() as i64
+    }
+}
+
+/// Where a user's daily quota comes from
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum QuotaTier {
+    Free,
+    /// The user has a Light University subscription
+    Subscriber,
+    /// An admin has set the user's quota
+    Custom,
+}
+
+#[derive(Debug, Clone, Default, FromQueryResult)]
+pub struct UsageTotals {
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_micro_usd: i64,
+}
+
+/// The start of the current UTC day, when quotas reset
+pub fn start_of_day() -> DateTime<Utc> {
+    DateTime::<Utc>::from_utc(
+        Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time"),
+        Utc,
+    )
+}
+
+/// Settles a reserved request with the tokens it used, and their cost at
+/// the configured prices
+pub async fn settle_usage<C: ConnectionTrait>(
+    db: &C,
+    usage_id: Uuid,
+    usage: &TokenUsage,
+) -> Result<ai_usage::Model, sea_orm::DbErr> {
+    ai_usage::ActiveModel {
+        id: Set(usage_id),
+        model: Set(usage.model.clone()),
+        prompt_tokens: Set(usage.prompt_tokens),
+        completion_tokens: Set(usage.completion_tokens),
+        cost_micro_usd: Set(usage.cost_micro_usd(
+            SECRET_VARIABLES.assessment_prompt_price,
+            SECRET_VARIABLES.assessment_completion_price,
+        )),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+/// The user's requests since `since`
+pub async fn usage_since<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<UsageTotals, sea_orm::DbErr> {
+    let statement = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT
+            count(*)::bigint AS requests,
+            coalesce(sum(prompt_tokens), 0)::bigint AS prompt_tokens,
+            coalesce(sum(completion_tokens), 0)::bigint AS completion_tokens,
+            coalesce(sum(cost_micro_usd), 0)::bigint AS cost_micro_usd
+        FROM ai_usage
+        WHERE user_id = $1 AND created_at >= $2
+        "#,
+        [user_id.into(), since.into()],
+    );
+
+    Ok(UsageTotals::find_by_statement(statement)
+        .one(db)
+        .await?
+        .unwrap_or_default())
+}
+
+async fn custom_quota<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+) -> Result<Option<i32>, sea_orm::DbErr> {
+    Ok(ai_quotas::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .map(|quota| quota.daily_requests))
+}
+
+async fn is_subscriber(user: &User) -> async_graphql::Result<bool> {
+    let Some(stripe_customer_id) = &user.stripe_customer_id else {
+        return Ok(false);
+    };
+
+    Ok(matches!(
+        light_university_subscription(stripe_customer_id)
+            .await?
+            .map(|subscription| subscription.status),
+        Some(stripe::SubscriptionStatus::Active | stripe::SubscriptionStatus::Trialing)
+    ))
+}
+
+/// How many requests the user can make a day, and why
+pub async fn daily_quota<C: ConnectionTrait>(
+    db: &C,
+    user: &User,
+) -> async_graphql::Result<(QuotaTier, i32)> {
+    if let Some(quota) = custom_quota(db, user.id).await? {
+        return Ok((QuotaTier::Custom, quota));
+    }
+
+    Ok(match is_subscriber(user).await? {
+        true => (
+            QuotaTier::Subscriber,
+            SECRET_VARIABLES.ai_subscriber_daily_quota,
+        ),
+        false => (QuotaTier::Free, SECRET_VARIABLES.ai_daily_quota),
+    })
+}
+
+/// Counts a request against today's quota and records it with no tokens
+/// yet, or returns `None` if the user has already made `quota` requests
+async fn try_reserve(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    quota: i32,
+    feature: &str,
+    model: &str,
+) -> Result<Option<Uuid>, sea_orm::DbErr> {
+    if quota <= 0 {
+        return Ok(None);
+    }
+
+    let txn = db.begin().await?;
+
+    // the row lock taken by the upsert makes concurrent requests wait
+    // for each other, so only `quota` of them can ever succeed
+    let reserved = txn
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            INSERT INTO ai_daily_requests (user_id, day, requests)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (user_id, day) DO UPDATE
+            SET requests = ai_daily_requests.requests + 1
+            WHERE ai_daily_requests.requests < $3
+            RETURNING requests
+            "#,
+            [
+                user_id.into(),
+                start_of_day().date_naive().into(),
+                quota.into(),
+            ],
+        ))
+        .await?;
+    if reserved.is_none() {
+        return Ok(None);
+    }
+
+    let usage = ai_usage::Model {
+        id: Uuid::new_v4(),
+        user_id: Some(user_id),
+        feature: feature.to_string(),
+        model: model.to_string(),
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        cost_micro_usd: 0,
+        created_at: Utc::now(),
+    }
+    .into_active_model()
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Some(usage.id))
+}
+
+/// Reserves one of the user's requests for today before a model is called,
+/// and returns the usage record to settle with [`settle_usage`]. Fails with
+/// `AI_QUOTA_EXCEEDED` if the user has no requests left.
+pub async fn reserve_request(
+    db: &DatabaseConnection,
+    user: &User,
+    feature: &str,
+    model: &str,
+) -> async_graphql::Result<Uuid> {
+    let custom_quota = custom_quota(db, user.id).await?;
+
+    // only ask Stripe once the user is past the free quota
+    let mut quota = custom_quota.unwrap_or(SECRET_VARIABLES.ai_daily_quota);
+    let mut reserved = try_reserve(db, user.id, quota, feature, model).await?;
+    if reserved.is_none() && custom_quota.is_none() {
+        quota = daily_quota(db, user).await?.1;
+        reserved = try_reserve(db, user.id, quota, feature, model).await?;
+    }
+
+    reserved.ok_or_else(|| {
+        new_err(
+            "AI_QUOTA_EXCEEDED",
+            &format!(
+                "You have used all {} of your AI requests for today, try again tomorrow",
+                quota
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_token_usage() {
+        let usage = TokenUsage {
+            model: "gpt-4-0613".to_string(),
+            prompt_tokens: 1000,
+            completion_tokens: 250,
+        };
+
+        // $0.03 and $0.015
+        assert_eq!(usage.cost_micro_usd(30.0, 60.0), 45_000);
+        assert_eq!(TokenUsage::default().cost_micro_usd(30.0, 60.0), 0);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::new_err;
+
+/// Uploads larger than this are rejected before they reach the resolver
+pub const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// The document types applicants may upload, and the bytes every file of
+/// that type starts with
+const ALLOWED_DOCUMENT_TYPES: &[(&str, &[u8])] = &[
+    ("application/pdf", b"%PDF-"),
+    ("image/png", b"\x89PNG\r\n\x1a\n"),
+    ("image/jpeg", b"\xff\xd8\xff"),
+];
+
+/// Checks an uploaded document's size and type, and returns its content type.
+///
+/// The content type the client sent is only trusted if the file's contents
+/// actually match it.
+pub fn validate_document(
+    content_type: Option<&str>,
+    content: &[u8],
+) -> async_graphql::Result<&'static str> {
+    if content.len() > MAX_DOCUMENT_SIZE {
+        return Err(new_err(
+            "DOCUMENT_TOO_LARGE",
+            &format!(
+                "Documents must be smaller than {} MB",
+                MAX_DOCUMENT_SIZE / 1024 / 1024
+            ),
+        ));
+    }
+
+    if content.is_empty() {
+        return Err(new_err("INVALID_DOCUMENT", "Document is empty"));
+    }
+
+    let content_type = content_type.unwrap_or_default().trim().to_lowercase();
+    let (allowed_type, magic_bytes) = ALLOWED_DOCUMENT_TYPES
+        .iter()
+        .find(|(allowed_type, _)| *allowed_type == content_type)
+        .ok_or_else(|| {
+            new_err(
+                "UNSUPPORTED_DOCUMENT_TYPE",
+                "Documents must be PDF, PNG or JPEG files",
+            )
+        })?;
+
+    if !content.starts_with(magic_bytes) {
+        return Err(new_err(
+            "INVALID_DOCUMENT",
+            &format!("Document contents are not a valid {} file", allowed_type),
+        ));
+    }
+
+    Ok(allowed_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_matching_types() {
+        assert_eq!(
+            validate_document(Some("application/pdf"), b"%PDF-1.7\n").unwrap(),
+            "application/pdf"
+        );
+        assert_eq!(
+            validate_document(Some("IMAGE/PNG"), b"\x89PNG\r\n\x1a\n\0\0").unwrap(),
+            "image/png"
+        );
+        assert_eq!(
+            validate_document(Some("image/jpeg"), b"\xff\xd8\xff\xe0").unwrap(),
+            "image/jpeg"
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_types() {
+        assert!(validate_document(Some("text/html"), b"<html>").is_err());
+        assert!(validate_document(None, b"%PDF-1.7").is_err());
+    }
+
+    #[test]
+    fn rejects_mismatched_contents() {
+        let err = validate_document(Some("application/pdf"), b"\x89PNG\r\n\x1a\n").unwrap_err();
+
+        assert_eq!(
+            err.message,
+            "Document contents are not a valid application/pdf file"
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_oversized_documents() {
+        assert!(validate_document(Some("application/pdf"), b"").is_err());
+
+        let mut content = b"%PDF-".to_vec();
+        content.resize(MAX_DOCUMENT_SIZE + 1, 0);
+        assert!(validate_document(Some("application/pdf"), &content).is_err());
+    }
+}
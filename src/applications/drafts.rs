@@ -0,0 +1,339 @@
+use std::fmt;
+
+use async_graphql::{InputObject, MaybeUndefined, OneofObject, SimpleObject, Union};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{ApplicationKind, CitizenshipApplication, CitizenshipStatus};
+
+/// Drafts expire this long after they were last saved. Expired drafts are
+/// hidden, and deleted when the user starts a new draft.
+pub fn draft_lifetime() -> Duration {
+    Duration::days(30)
+}
+
+/// A problem with one field of an application
+#[derive(SimpleObject, Serialize, Clone, Debug, PartialEq, Eq)]
+#[graphql(rename_fields = "snake_case")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// A citizenship application that is still being filled in. Any field may
+/// be missing until the draft is submitted.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CitizenshipApplicationDraft {
+    #[serde(default, with = "chrono::serde::ts_milliseconds_option")]
+    pub date_of_birth: Option<DateTime<Utc>>,
+    pub sex: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub skills: Option<Vec<String>>,
+    pub occupations: Option<Vec<String>>,
+    pub country_of_citizenship: Option<Vec<String>>,
+    pub country_of_birth: Option<String>,
+    pub country_of_residence: Option<String>,
+    pub ethnic_groups: Option<Vec<String>>,
+}
+
+/// Changes to a citizenship application draft. Fields that are left out
+/// are kept as they were, and fields set to `null` are cleared.
+#[derive(InputObject, Debug, Clone, Default)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CitizenshipApplicationDraftInput {
+    pub date_of_birth: MaybeUndefined<DateTime<Utc>>,
+    pub sex: MaybeUndefined<String>,
+    pub first_name: MaybeUndefined<String>,
+    pub last_name: MaybeUndefined<String>,
+    pub skills: MaybeUndefined<Vec<String>>,
+    pub occupations: MaybeUndefined<Vec<String>>,
+    pub country_of_citizenship: MaybeUndefined<Vec<String>>,
+    pub country_of_birth: MaybeUndefined<String>,
+    pub country_of_residence: MaybeUndefined<String>,
+    pub ethnic_groups: MaybeUndefined<Vec<String>>,
+}
+
+fn apply<T>(field: &mut Option<T>, change: MaybeUndefined<T>) {
+    match change {
+        MaybeUndefined::Undefined => {}
+        MaybeUndefined::Null => *field = None,
+        MaybeUndefined::Value(value) => *field = Some(value),
+    }
+}
+
+fn check_non_empty(errors: &mut Vec<FieldError>, field: &str, value: &Option<String>) {
+    match value {
+        None => errors.push(FieldError::new(field, "is required")),
+        Some(value) if value.trim().is_empty() => {
+            errors.push(FieldError::new(field, "must not be empty"))
+        }
+        Some(_) => {}
+    }
+}
+
+impl CitizenshipApplicationDraft {
+    pub fn apply(&mut self, input: CitizenshipApplicationDraftInput) {
+        apply(&mut self.date_of_birth, input.date_of_birth);
+        apply(&mut self.sex, input.sex);
+        apply(&mut self.first_name, input.first_name);
+        apply(&mut self.last_name, input.last_name);
+        apply(&mut self.skills, input.skills);
+        apply(&mut self.occupations, input.occupations);
+        apply(
+            &mut self.country_of_citizenship,
+            input.country_of_citizenship,
+        );
+        apply(&mut self.country_of_birth, input.country_of_birth);
+        apply(&mut self.country_of_residence, input.country_of_residence);
+        apply(&mut self.ethnic_groups, input.ethnic_groups);
+    }
+
+    /// Every field that is missing or invalid, in the order they appear on
+    /// the application form. The draft can be submitted once this is empty.
+    pub fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        match self.date_of_birth {
+            None => errors.push(FieldError::new("date_of_birth", "is required")),
+            Some(date_of_birth) if date_of_birth > Utc::now() => {
+                errors.push(FieldError::new("date_of_birth", "must be in the past"))
+            }
+            Some(_) => {}
+        }
+
+        check_non_empty(&mut errors, "sex", &self.sex);
+        check_non_empty(&mut errors, "first_name", &self.first_name);
+        check_non_empty(&mut errors, "last_name", &self.last_name);
+
+        if !matches!(&self.country_of_citizenship, Some(countries) if !countries.is_empty()) {
+            errors.push(FieldError::new(
+                "country_of_citizenship",
+                "must contain at least one country",
+            ));
+        }
+
+        check_non_empty(&mut errors, "country_of_birth", &self.country_of_birth);
+        check_non_empty(
+            &mut errors,
+            "country_of_residence",
+            &self.country_of_residence,
+        );
+
+        errors
+    }
+
+    /// Turns a complete draft into an application, or returns its field errors
+    pub fn into_application(self) -> Result<CitizenshipApplication, Vec<FieldError>> {
+        let errors = self.field_errors();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(CitizenshipApplication {
+            date_of_birth: self.date_of_birth.unwrap_or_default(),
+            sex: self.sex.unwrap_or_default(),
+            first_name: self.first_name.unwrap_or_default(),
+            last_name: self.last_name.unwrap_or_default(),
+            skills: self.skills.unwrap_or_default(),
+            occupations: self.occupations.unwrap_or_default(),
+            country_of_citizenship: self.country_of_citizenship.unwrap_or_default(),
+            country_of_birth: self.country_of_birth.unwrap_or_default(),
+            country_of_residence: self.country_of_residence.unwrap_or_default(),
+            ethnic_groups: self.ethnic_groups.unwrap_or_default(),
+            citizenship_status: CitizenshipStatus::Pending,
+        })
+    }
+}
+
+impl From<CitizenshipApplication> for CitizenshipApplicationDraft {
+    fn from(application: CitizenshipApplication) -> Self {
+        Self {
+            date_of_birth: Some(application.date_of_birth),
+            sex: Some(application.sex),
+            first_name: Some(application.first_name),
+            last_name: Some(application.last_name),
+            skills: Some(application.skills),
+            occupations: Some(application.occupations),
+            country_of_citizenship: Some(application.country_of_citizenship),
+            country_of_birth: Some(application.country_of_birth),
+            country_of_residence: Some(application.country_of_residence),
+            ethnic_groups: Some(application.ethnic_groups),
+        }
+    }
+}
+
+/// The typed contents of a draft, stored like [`super::ApplicationKind`]
+/// in the `application_type` and `draft` columns
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Union)]
+#[serde(tag = "application_type", content = "draft", rename_all = "snake_case")]
+pub enum ApplicationDraftKind {
+    Citizenship(CitizenshipApplicationDraft),
+}
+
+impl ApplicationDraftKind {
+    pub fn from_columns(application_type: &str, draft: Value) -> serde_json::Result<Self> {
+        serde_json::from_value(serde_json::json!({
+            "application_type": application_type,
+            "draft": draft,
+        }))
+    }
+
+    /// Returns the `application_type` and `draft` column values
+    pub fn into_columns(self) -> serde_json::Result<(String, Value)> {
+        let mut value = serde_json::to_value(self)?;
+
+        match (value["application_type"].take(), value["draft"].take()) {
+            (Value::String(application_type), draft) => Ok((application_type, draft)),
+            _ => unreachable!("ApplicationDraftKind is always serialised with a string tag"),
+        }
+    }
+
+    pub fn field_errors(&self) -> Vec<FieldError> {
+        match self {
+            ApplicationDraftKind::Citizenship(draft) => draft.field_errors(),
+        }
+    }
+
+    pub fn into_application(self) -> Result<ApplicationKind, Vec<FieldError>> {
+        match self {
+            ApplicationDraftKind::Citizenship(draft) => {
+                draft.into_application().map(ApplicationKind::Citizenship)
+            }
+        }
+    }
+}
+
+#[derive(OneofObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub enum ApplicationDraftInput {
+    Citizenship(CitizenshipApplicationDraftInput),
+}
+
+impl ApplicationDraftInput {
+    /// Applies the changes to an existing draft, or to an empty draft if
+    /// there is none. Returns `None` if the draft is for a different type
+    /// of application.
+    pub fn apply_to(self, draft: Option<ApplicationDraftKind>) -> Option<ApplicationDraftKind> {
+        match (self, draft) {
+            (ApplicationDraftInput::Citizenship(input), None) => {
+                let mut draft = CitizenshipApplicationDraft::default();
+                draft.apply(input);
+                Some(ApplicationDraftKind::Citizenship(draft))
+            }
+            (
+                ApplicationDraftInput::Citizenship(input),
+                Some(ApplicationDraftKind::Citizenship(mut draft)),
+            ) => {
+                draft.apply(input);
+                Some(ApplicationDraftKind::Citizenship(draft))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn complete_draft() -> CitizenshipApplicationDraft {
+        CitizenshipApplicationDraft {
+            date_of_birth: Some(Utc.with_ymd_and_hms(1990, 1, 1, 0, 0, 0).unwrap()),
+            sex: Some("FEMALE".to_string()),
+            first_name: Some("Jane".to_string()),
+            last_name: Some("Doe".to_string()),
+            skills: None,
+            occupations: None,
+            country_of_citizenship: Some(vec!["AU".to_string()]),
+            country_of_birth: Some("AU".to_string()),
+            country_of_residence: Some("AU".to_string()),
+            ethnic_groups: None,
+        }
+    }
+
+    #[test]
+    fn empty_draft_reports_required_fields() {
+        let fields = CitizenshipApplicationDraft::default()
+            .field_errors()
+            .into_iter()
+            .map(|error| error.field)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fields,
+            [
+                "date_of_birth",
+                "sex",
+                "first_name",
+                "last_name",
+                "country_of_citizenship",
+                "country_of_birth",
+                "country_of_residence",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_fields() {
+        let mut draft = complete_draft();
+        draft.first_name = Some(" ".to_string());
+
+        assert_eq!(
+            draft.field_errors(),
+            [FieldError::new("first_name", "must not be empty")]
+        );
+    }
+
+    #[test]
+    fn applies_partial_changes() {
+        let mut draft = complete_draft();
+        draft.apply(CitizenshipApplicationDraftInput {
+            first_name: MaybeUndefined::Value("Janet".to_string()),
+            last_name: MaybeUndefined::Null,
+            ..Default::default()
+        });
+
+        assert_eq!(draft.first_name.as_deref(), Some("Janet"));
+        assert_eq!(draft.last_name, None);
+        assert_eq!(draft.sex.as_deref(), Some("FEMALE"));
+    }
+
+    #[test]
+    fn complete_draft_becomes_pending_application() {
+        let application = complete_draft().into_application().unwrap();
+
+        assert_eq!(application.citizenship_status, CitizenshipStatus::Pending);
+        assert!(application.skills.is_empty());
+    }
+
+    #[test]
+    fn round_trips_columns() {
+        let draft = ApplicationDraftKind::Citizenship(complete_draft());
+        let (application_type, value) = draft.clone().into_columns().unwrap();
+
+        assert_eq!(application_type, "citizenship");
+        assert_eq!(
+            ApplicationDraftKind::from_columns(&application_type, value).unwrap(),
+            draft
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use async_graphql::{Enum, ErrorExtensions, SimpleObject};
+use chrono::{Datelike, NaiveDate, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::Serialize;
+
+use crate::{
+    applications::{
+        forms::CITIZENSHIP_APPLICATION_TYPE, ApplicationKind, CitizenshipApplication,
+        CitizenshipStatus,
+    },
+    citizens::find_citizen,
+    error::new_err,
+    graphql::types::user::User,
+    schema::applications,
+    util::variables::SECRET_VARIABLES,
+};
+
+pub const MINIMUM_CITIZENSHIP_AGE: i32 = 18;
+
+/// The partial unique index on open citizenship applications in `schema.sql`
+const OPEN_APPLICATION_INDEX: &str = "index_applications_open_citizenship";
+
+const OPEN_APPLICATION_MESSAGE: &str = "You already have an application waiting for a decision";
+
+/// The rules an applicant has to meet before they can apply for citizenship
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EligibilityRule {
+    MinimumAge,
+    EmailVerified,
+    NoOpenApplication,
+    NotAlreadyCitizen,
+    CountryNotRestricted,
+}
+
+#[derive(SimpleObject, Clone, Debug, Serialize)]
+#[graphql(rename_fields = "snake_case")]
+pub struct EligibilityFailure {
+    pub rule: EligibilityRule,
+    pub message: String,
+}
+
+impl EligibilityFailure {
+    fn new(rule: EligibilityRule, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+        }
+    }
+}
+
+/// Age in whole years on `today`
+pub fn age_on(date_of_birth: NaiveDate, today: NaiveDate) -> i32 {
+    let had_birthday = (today.month(), today.day()) >= (date_of_birth.month(), date_of_birth.day());
+
+    today.year() - date_of_birth.year() - if had_birthday { 0 } else { 1 }
+}
+
+/// Rules that only depend on the application itself
+fn application_failures(
+    application: &CitizenshipApplication,
+    restricted_countries: &[String],
+    today: NaiveDate,
+) -> Vec<EligibilityFailure> {
+    let mut failures = vec![];
+
+    if age_on(application.date_of_birth.date_naive(), today) < MINIMUM_CITIZENSHIP_AGE {
+        failures.push(EligibilityFailure::new(
+            EligibilityRule::MinimumAge,
+            format!(
+                "You must be at least {} years old to apply",
+                MINIMUM_CITIZENSHIP_AGE
+            ),
+        ));
+    }
+
+    let restricted = std::iter::once(&application.country_of_residence)
+        .chain(application.country_of_citizenship.iter())
+        .find(|country| {
+            restricted_countries
+                .iter()
+                .any(|restricted| restricted.eq_ignore_ascii_case(country.trim()))
+        });
+
+    if let Some(country) = restricted {
+        failures.push(EligibilityFailure::new(
+            EligibilityRule::CountryNotRestricted,
+            format!(
+                "We can't currently accept applications involving {}",
+                country
+            ),
+        ));
+    }
+
+    failures
+}
+
+/// Returns every rule the user and application fail, or an empty list if
+/// the user is eligible for citizenship
+pub async fn citizenship_eligibility_failures(
+    db: &DatabaseConnection,
+    user: &User,
+    application: &CitizenshipApplication,
+) -> async_graphql::Result<Vec<EligibilityFailure>> {
+    let mut failures = application_failures(
+        application,
+        &SECRET_VARIABLES.citizenship_restricted_countries,
+        Utc::now().date_naive(),
+    );
+
+    if user.email_verified_at.is_none() {
+        failures.push(EligibilityFailure::new(
+            EligibilityRule::EmailVerified,
+            "You must verify your email address before applying",
+        ));
+    }
+
+    let statuses = applications::Entity::find()
+        .filter(applications::Column::UserId.eq(user.id))
+        .filter(applications::Column::ApplicationType.eq(CITIZENSHIP_APPLICATION_TYPE))
+        .all(db)
+        .await?
+        .iter()
+        .filter_map(|model| match ApplicationKind::try_from(model) {
+            Ok(ApplicationKind::Citizenship(application)) => Some(application.citizenship_status),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let is_citizen = find_citizen(db, user.id)
+        .await?
+        .is_some_and(|citizen| citizen.status.is_citizen());
+
+    if is_citizen {
+        failures.push(EligibilityFailure::new(
+            EligibilityRule::NotAlreadyCitizen,
+            "You are already a citizen",
+        ));
+    } else if statuses.iter().any(|status| {
+        matches!(
+            status,
+            CitizenshipStatus::Pending | CitizenshipStatus::InformationRequested
+        )
+    }) {
+        failures.push(EligibilityFailure::new(
+            EligibilityRule::NoOpenApplication,
+            OPEN_APPLICATION_MESSAGE,
+        ));
+    }
+
+    Ok(failures)
+}
+
+/// Fails with a `NOT_ELIGIBLE` error listing the failed rules in its `failures` extension
+pub async fn ensure_citizenship_eligibility(
+    db: &DatabaseConnection,
+    user: &User,
+    application: &CitizenshipApplication,
+) -> async_graphql::Result<()> {
+    let failures = citizenship_eligibility_failures(db, user, application).await?;
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    Err(not_eligible(&failures))
+}
+
+/// Turns a second open application, submitted concurrently with the first
+/// so both passed [`ensure_citizenship_eligibility`], into the same
+/// `NOT_ELIGIBLE` error
+pub fn open_application_conflict(err: DbErr) -> async_graphql::Error {
+    if !err.to_string().contains(OPEN_APPLICATION_INDEX) {
+        return err.into();
+    }
+
+    let failures = [EligibilityFailure::new(
+        EligibilityRule::NoOpenApplication,
+        OPEN_APPLICATION_MESSAGE,
+    )];
+    not_eligible(&failures)
+}
+
+fn not_eligible(failures: &[EligibilityFailure]) -> async_graphql::Error {
+    let message = failures
+        .iter()
+        .map(|failure| failure.message.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let failures = match async_graphql::to_value(failures) {
+        Ok(failures) => failures,
+        Err(e) => return e.into(),
+    };
+
+    new_err("NOT_ELIGIBLE", &message).extend_with(|_, e| e.set("failures", failures.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::*;
+
+    fn application(date_of_birth: (i32, u32, u32), residence: &str) -> CitizenshipApplication {
+        CitizenshipApplication {
+            date_of_birth: Utc
+                .with_ymd_and_hms(date_of_birth.0, date_of_birth.1, date_of_birth.2, 0, 0, 0)
+                .unwrap(),
+            sex: "FEMALE".into(),
+            first_name: "Jane".into(),
+            last_name: "Doe".into(),
+            skills: vec![],
+            occupations: vec![],
+            country_of_citizenship: vec!["AU".into()],
+            country_of_birth: "AU".into(),
+            country_of_residence: residence.into(),
+            ethnic_groups: vec![],
+            citizenship_status: CitizenshipStatus::Pending,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn age_counts_birthdays() {
+        assert_eq!(age_on(date(2000, 6, 15), date(2018, 6, 14)), 17);
+        assert_eq!(age_on(date(2000, 6, 15), date(2018, 6, 15)), 18);
+        assert_eq!(age_on(date(2000, 2, 29), date(2018, 3, 1)), 18);
+    }
+
+    #[test]
+    fn fails_for_minors() {
+        let failures =
+            application_failures(&application((2010, 1, 1), "AU"), &[], date(2023, 1, 1));
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].rule, EligibilityRule::MinimumAge);
+    }
+
+    #[test]
+    fn fails_for_restricted_countries() {
+        let failures = application_failures(
+            &application((1990, 1, 1), "xx"),
+            &["XX".to_string()],
+            date(2023, 1, 1),
+        );
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].rule, EligibilityRule::CountryNotRestricted);
+    }
+
+    #[test]
+    fn adults_from_unrestricted_countries_pass() {
+        let failures = application_failures(
+            &application((1990, 1, 1), "AU"),
+            &["XX".to_string()],
+            date(2023, 1, 1),
+        );
+
+        assert!(failures.is_empty());
+    }
+}
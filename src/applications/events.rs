@@ -0,0 +1,114 @@
+//! The history of each application.
+//!
+//! Application status lives inside the mutable `applications.application`
+//! JSON, so everything that happens to an application is also recorded as
+//! an append-only event. Events are never changed or deleted, except along
+//! with their application.
+
+use async_graphql::Enum;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DeriveActiveEnum, EnumIter, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CitizenshipStatus;
+use crate::{error::new_err, schema::application_events};
+
+#[derive(
+    Enum, Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, EnumIter, DeriveActiveEnum,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+pub enum ApplicationEventKind {
+    #[sea_orm(string_value = "Submitted")]
+    Submitted,
+    #[sea_orm(string_value = "StatusChanged")]
+    StatusChanged,
+    #[sea_orm(string_value = "CommentAdded")]
+    CommentAdded,
+    #[sea_orm(string_value = "DocumentRequested")]
+    DocumentRequested,
+}
+
+/// An event that hasn't been recorded yet
+#[derive(Debug, Clone)]
+pub struct NewApplicationEvent {
+    pub kind: ApplicationEventKind,
+    pub actor_id: Option<Uuid>,
+    pub status: Option<CitizenshipStatus>,
+    pub message: Option<String>,
+    pub internal: bool,
+}
+
+impl NewApplicationEvent {
+    pub fn submitted(applicant_id: Uuid) -> Self {
+        Self {
+            kind: ApplicationEventKind::Submitted,
+            actor_id: Some(applicant_id),
+            status: None,
+            message: None,
+            internal: false,
+        }
+    }
+
+    pub fn status_changed(
+        reviewer_id: Uuid,
+        status: CitizenshipStatus,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            kind: ApplicationEventKind::StatusChanged,
+            actor_id: Some(reviewer_id),
+            status: Some(status),
+            message: reason,
+            internal: false,
+        }
+    }
+
+    pub fn comment_added(reviewer_id: Uuid, comment: String, internal: bool) -> Self {
+        Self {
+            kind: ApplicationEventKind::CommentAdded,
+            actor_id: Some(reviewer_id),
+            status: None,
+            message: Some(comment),
+            internal,
+        }
+    }
+
+    pub fn document_requested(reviewer_id: Uuid, document: String) -> Self {
+        Self {
+            kind: ApplicationEventKind::DocumentRequested,
+            actor_id: Some(reviewer_id),
+            status: None,
+            message: Some(document),
+            internal: false,
+        }
+    }
+}
+
+/// Comments and document requests need something to say
+pub fn validate_event_message(message: &str) -> async_graphql::Result<()> {
+    match message.trim().is_empty() {
+        true => Err(new_err("INVALID_MESSAGE", "Message must not be empty")),
+        false => Ok(()),
+    }
+}
+
+pub async fn record_event<C: ConnectionTrait>(
+    db: &C,
+    application_id: Uuid,
+    event: NewApplicationEvent,
+) -> Result<application_events::Model, sea_orm::DbErr> {
+    application_events::Model {
+        id: Uuid::new_v4(),
+        application_id,
+        kind: event.kind,
+        actor_id: event.actor_id,
+        status: event.status,
+        message: event.message,
+        internal: event.internal,
+        created_at: Utc::now(),
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+}
@@ -0,0 +1,288 @@
+//! Form definitions describe what an application of each type contains.
+//!
+//! Each form is a JSON Schema that submitted `applications.application`
+//! payloads are validated against. Forms are versioned: publishing a form
+//! again creates a new version, and applications remember the version they
+//! were submitted with. Conditional fields are expressed with the schema's
+//! own keywords, e.g. `if`/`then`/`else` or `dependencies`.
+//!
+//! Citizenship is a built-in form. Its schema lives here rather than in the
+//! database, and describes the fields of [`super::CitizenshipApplication`]
+//! that applicants fill in. Citizenship applications are validated against
+//! it when they are submitted.
+
+use async_graphql::{InputObject, Json};
+use chrono::{DateTime, Utc};
+use jsonschema::{error::ValidationErrorKind, JSONSchema};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::Value;
+
+use super::drafts::FieldError;
+use crate::{error::new_err, schema::application_forms};
+
+pub const CITIZENSHIP_APPLICATION_TYPE: &str = "citizenship";
+
+/// Application types whose forms are defined in code
+pub const BUILT_IN_APPLICATION_TYPES: &[&str] = &[CITIZENSHIP_APPLICATION_TYPE];
+
+const CITIZENSHIP_FORM_SCHEMA: &str = include_str!("citizenship.json");
+
+/// One version of an application form
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormDefinition {
+    pub application_type: String,
+    pub version: i32,
+    pub title: String,
+    pub description: Option<String>,
+    pub schema: Value,
+    /// Built-in forms have no creation date
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<application_forms::Model> for FormDefinition {
+    fn from(model: application_forms::Model) -> Self {
+        Self {
+            application_type: model.application_type,
+            version: model.version,
+            title: model.title,
+            description: model.description,
+            schema: model.schema,
+            created_at: Some(model.created_at),
+        }
+    }
+}
+
+pub fn citizenship_form() -> FormDefinition {
+    FormDefinition {
+        application_type: CITIZENSHIP_APPLICATION_TYPE.to_string(),
+        version: 1,
+        title: "Citizenship".to_string(),
+        description: Some("Apply to become a citizen of Lumina".to_string()),
+        schema: serde_json::from_str(CITIZENSHIP_FORM_SCHEMA)
+            .expect("built-in citizenship form schema is valid JSON"),
+        created_at: None,
+    }
+}
+
+fn built_in_form(application_type: &str) -> Option<FormDefinition> {
+    match application_type {
+        CITIZENSHIP_APPLICATION_TYPE => Some(citizenship_form()),
+        _ => None,
+    }
+}
+
+/// An application for a form defined in the database
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct FormApplication {
+    pub application_type: String,
+    pub form_version: i32,
+    pub data: Json<Value>,
+}
+
+#[derive(InputObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct FormApplicationInput {
+    pub application_type: String,
+    /// Must match the latest version of the form's schema
+    pub data: Json<Value>,
+}
+
+/// Application types are used as the `application_type` column and in
+/// the union of application kinds, so they are kept to snake_case
+pub fn validate_application_type(application_type: &str) -> async_graphql::Result<()> {
+    let mut chars = application_type.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    match valid {
+        true => Ok(()),
+        false => Err(new_err(
+            "INVALID_APPLICATION_TYPE",
+            "Application types must be snake_case, e.g. business_registration",
+        )),
+    }
+}
+
+/// Compiles a form's schema. Forms must describe a JSON object.
+pub fn compile_form_schema(schema: &Value) -> async_graphql::Result<JSONSchema> {
+    if schema.get("type") != Some(&Value::String("object".to_string())) {
+        return Err(new_err(
+            "INVALID_FORM_SCHEMA",
+            "Form schemas must have \"type\": \"object\"",
+        ));
+    }
+
+    JSONSchema::compile(schema).map_err(|e| new_err("INVALID_FORM_SCHEMA", &e.to_string()))
+}
+
+/// Every field of `data` that doesn't match the form's schema.
+///
+/// Fields are given as dotted paths, e.g. `business.name` or `owners.0`.
+pub fn form_field_errors(schema: &JSONSchema, data: &Value) -> Vec<FieldError> {
+    let errors = match schema.validate(data) {
+        Ok(()) => return Vec::new(),
+        Err(errors) => errors,
+    };
+
+    errors
+        .map(|error| {
+            let mut path = error.instance_path.clone().into_vec();
+
+            // report missing fields against the field itself, not its parent
+            if let ValidationErrorKind::Required {
+                property: Value::String(property),
+            } = &error.kind
+            {
+                path.push(property.clone());
+            }
+
+            let field = match path.is_empty() {
+                true => "application".to_string(),
+                false => path.join("."),
+            };
+
+            FieldError {
+                field,
+                message: error.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Finds a version of a form, or its latest version if no version is given
+pub async fn find_form(
+    db: &DatabaseConnection,
+    application_type: &str,
+    version: Option<i32>,
+) -> async_graphql::Result<Option<FormDefinition>> {
+    if let Some(form) = built_in_form(application_type) {
+        return Ok((version.is_none() || version == Some(form.version)).then_some(form));
+    }
+
+    let mut query = application_forms::Entity::find()
+        .filter(application_forms::Column::ApplicationType.eq(application_type));
+
+    if let Some(version) = version {
+        query = query.filter(application_forms::Column::Version.eq(version));
+    }
+
+    Ok(query
+        .order_by_desc(application_forms::Column::Version)
+        .one(db)
+        .await?
+        .map(FormDefinition::from))
+}
+
+/// Checks a submitted payload against the version of the form it was
+/// submitted for
+pub async fn form_application_field_errors(
+    db: &DatabaseConnection,
+    application_type: &str,
+    version: i32,
+    data: &Value,
+) -> async_graphql::Result<Vec<FieldError>> {
+    let form = find_form(db, application_type, Some(version))
+        .await?
+        .ok_or_else(|| {
+            new_err(
+                "FORM_NOT_FOUND",
+                &format!(
+                    "No {} application form with version {}",
+                    application_type, version
+                ),
+            )
+        })?;
+
+    Ok(form_field_errors(&compile_form_schema(&form.schema)?, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::applications::{CitizenshipApplication, CitizenshipStatus};
+
+    fn business_schema() -> JSONSchema {
+        compile_form_schema(&json!({
+            "type": "object",
+            "required": ["name", "structure"],
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "structure": { "enum": ["sole_trader", "company"] },
+                "company_number": { "type": "string" }
+            },
+            "if": { "properties": { "structure": { "const": "company" } } },
+            "then": { "required": ["company_number"] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn citizenship_form_matches_submitted_applications() {
+        let schema = compile_form_schema(&citizenship_form().schema).unwrap();
+        let application = CitizenshipApplication {
+            date_of_birth: Utc.with_ymd_and_hms(1990, 1, 1, 0, 0, 0).unwrap(),
+            sex: "FEMALE".to_string(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            skills: vec![],
+            occupations: vec![],
+            country_of_citizenship: vec!["AU".to_string()],
+            country_of_birth: "AU".to_string(),
+            country_of_residence: "AU".to_string(),
+            ethnic_groups: vec![],
+            citizenship_status: CitizenshipStatus::Pending,
+        };
+
+        let data = application.form_data().unwrap();
+
+        assert_eq!(form_field_errors(&schema, &data), vec![]);
+        assert_eq!(data.get("citizenship_status"), None);
+        assert_eq!(
+            citizenship_form().schema["properties"].get("citizenship_status"),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_missing_fields_by_name() {
+        let errors = form_field_errors(&business_schema(), &json!({ "structure": "sole_trader" }));
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "name");
+    }
+
+    #[test]
+    fn conditional_fields_are_required() {
+        let schema = business_schema();
+
+        assert_eq!(
+            form_field_errors(
+                &schema,
+                &json!({ "name": "Acme", "structure": "sole_trader" })
+            ),
+            vec![]
+        );
+
+        let errors = form_field_errors(&schema, &json!({ "name": "Acme", "structure": "company" }));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "company_number");
+    }
+
+    #[test]
+    fn rejects_schemas_that_are_not_objects() {
+        assert!(compile_form_schema(&json!({ "type": "string" })).is_err());
+        assert!(compile_form_schema(&json!({ "type": "object", "minProperties": "x" })).is_err());
+    }
+
+    #[test]
+    fn validates_application_types() {
+        assert!(validate_application_type("business_registration").is_ok());
+        assert!(validate_application_type("Residency").is_err());
+        assert!(validate_application_type("1grant").is_err());
+        assert!(validate_application_type("").is_err());
+    }
+}
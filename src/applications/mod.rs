@@ -1,12 +1,22 @@
-use async_graphql::Enum;
+pub mod documents;
+pub mod drafts;
+pub mod eligibility;
+pub mod events;
+pub mod forms;
+
+use async_graphql::{Enum, ErrorExtensions, InputObject, Json, OneofObject, SimpleObject, Union};
 use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, DeriveActiveEnum, EnumIter};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::{error::new_err, graphql::types::application::Application};
+use crate::{error::new_err, graphql::types::user::User, schema::applications};
+use drafts::{CitizenshipApplicationDraft, FieldError};
+use forms::{FormApplication, FormApplicationInput, CITIZENSHIP_APPLICATION_TYPE};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
 pub struct CitizenshipApplication {
-    pub user_id: uuid::Uuid,
     #[serde(with = "chrono::serde::ts_milliseconds")]
     pub date_of_birth: DateTime<Utc>,
     pub sex: String,
@@ -21,25 +31,287 @@ pub struct CitizenshipApplication {
     pub citizenship_status: CitizenshipStatus,
 }
 
-#[derive(Enum, Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(
+    Enum, Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, EnumIter, DeriveActiveEnum,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
 pub enum CitizenshipStatus {
+    #[sea_orm(string_value = "Pending")]
     Pending,
+    #[sea_orm(string_value = "InformationRequested")]
+    InformationRequested,
+    #[sea_orm(string_value = "Approved")]
     Approved,
+    #[sea_orm(string_value = "Rejected")]
     Rejected,
 }
 
-pub async fn validate_application(app: &Application) -> async_graphql::Result<()> {
-    match app.application_type.as_str() {
-        "citizenship" => {
-            serde_json::from_value::<CitizenshipApplication>(app.application.clone())
-                .map(|_| ())
-                .map_err(|e| new_err("INVALID_APPLICATION", &e.to_string()))
-            // TODO also check if user is eligible for a citizenship and doesn't
+impl CitizenshipStatus {
+    /// Whether a reviewer may move an application from this status to `next`.
+    /// Approved and rejected applications are final.
+    pub fn can_transition_to(self, next: CitizenshipStatus) -> bool {
+        use CitizenshipStatus::*;
+
+        matches!(
+            (self, next),
+            (Pending, Approved)
+                | (Pending, Rejected)
+                | (Pending, InformationRequested)
+                | (InformationRequested, Approved)
+                | (InformationRequested, Rejected)
+        )
+    }
+}
+
+/// The typed contents of an application.
+///
+/// Stored in the `application_type`, `application` and `form_version`
+/// columns of the `applications` table. Built-in forms are typed; any
+/// other application type is a [`FormApplication`] whose data was
+/// validated against its form when it was submitted.
+#[derive(Debug, Clone, Union)]
+pub enum ApplicationKind {
+    Citizenship(CitizenshipApplication),
+    Form(FormApplication),
+}
+
+impl ApplicationKind {
+    pub fn from_columns(
+        application_type: &str,
+        application: Value,
+        form_version: Option<i32>,
+    ) -> serde_json::Result<Self> {
+        match (application_type, form_version) {
+            (CITIZENSHIP_APPLICATION_TYPE, _) => {
+                serde_json::from_value(application).map(ApplicationKind::Citizenship)
+            }
+            (_, Some(form_version)) => Ok(ApplicationKind::Form(FormApplication {
+                application_type: application_type.to_string(),
+                form_version,
+                data: Json(application),
+            })),
+            (_, None) => Err(serde::de::Error::custom(format!(
+                "{} application has no form version",
+                application_type
+            ))),
+        }
+    }
+
+    /// Returns the `application_type` and `application` column values
+    pub fn into_columns(self) -> serde_json::Result<(String, Value)> {
+        match self {
+            ApplicationKind::Citizenship(application) => Ok((
+                CITIZENSHIP_APPLICATION_TYPE.to_string(),
+                serde_json::to_value(application)?,
+            )),
+            ApplicationKind::Form(application) => {
+                Ok((application.application_type, application.data.0))
+            }
+        }
+    }
+
+    /// The `form_version` column value. Built-in forms aren't versioned.
+    pub fn form_version(&self) -> Option<i32> {
+        match self {
+            ApplicationKind::Citizenship(_) => None,
+            ApplicationKind::Form(application) => Some(application.form_version),
+        }
+    }
+}
+
+impl TryFrom<&applications::Model> for ApplicationKind {
+    type Error = serde_json::Error;
+
+    fn try_from(model: &applications::Model) -> Result<Self, Self::Error> {
+        Self::from_columns(
+            &model.application_type,
+            model.application.clone(),
+            model.form_version,
+        )
+    }
+}
+
+#[derive(InputObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CitizenshipApplicationInput {
+    pub date_of_birth: DateTime<Utc>,
+    pub sex: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub skills: Vec<String>,
+    pub occupations: Vec<String>,
+    pub country_of_citizenship: Vec<String>,
+    pub country_of_birth: String,
+    pub country_of_residence: String,
+    pub ethnic_groups: Vec<String>,
+}
+
+/// An application submitted by a user.
+///
+/// Applicants can't choose who the application belongs to or what its
+/// status is, so those are filled in by the server.
+#[derive(OneofObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub enum ApplicationInput {
+    Citizenship(CitizenshipApplicationInput),
+    /// An application for a form defined in the database
+    Form(FormApplicationInput),
+}
+
+impl From<CitizenshipApplicationInput> for CitizenshipApplication {
+    fn from(input: CitizenshipApplicationInput) -> Self {
+        CitizenshipApplication {
+            date_of_birth: input.date_of_birth,
+            sex: input.sex,
+            first_name: input.first_name,
+            last_name: input.last_name,
+            skills: input.skills,
+            occupations: input.occupations,
+            country_of_citizenship: input.country_of_citizenship,
+            country_of_birth: input.country_of_birth,
+            country_of_residence: input.country_of_residence,
+            ethnic_groups: input.ethnic_groups,
+            citizenship_status: CitizenshipStatus::Pending,
+        }
+    }
+}
+
+impl ApplicationInput {
+    /// Applications for database forms are submitted against the latest
+    /// version of the form
+    pub async fn into_kind(
+        self,
+        db: &DatabaseConnection,
+    ) -> async_graphql::Result<ApplicationKind> {
+        match self {
+            ApplicationInput::Citizenship(input) => Ok(ApplicationKind::Citizenship(input.into())),
+            ApplicationInput::Form(input) => {
+                let form = forms::find_form(db, &input.application_type, None)
+                    .await?
+                    .ok_or_else(|| {
+                        new_err(
+                            "FORM_NOT_FOUND",
+                            &format!("No {} application form", input.application_type),
+                        )
+                    })?;
+
+                if forms::BUILT_IN_APPLICATION_TYPES.contains(&form.application_type.as_str()) {
+                    return Err(new_err(
+                        "INVALID_APPLICATION_TYPE",
+                        &format!(
+                            "Submit {} applications with their own input",
+                            form.application_type
+                        ),
+                    ));
+                }
+
+                Ok(ApplicationKind::Form(FormApplication {
+                    application_type: form.application_type,
+                    form_version: form.version,
+                    data: input.data,
+                }))
+            }
+        }
+    }
+}
+
+/// An `INVALID_APPLICATION` error listing every problem in its `field_errors` extension
+pub fn invalid_application_error(field_errors: &[FieldError]) -> async_graphql::Error {
+    let message = field_errors
+        .iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let field_errors = async_graphql::to_value(field_errors).unwrap_or_default();
+
+    new_err("INVALID_APPLICATION", &message)
+        .extend_with(|_, e| e.set("field_errors", field_errors.clone()))
+}
+
+impl CitizenshipApplication {
+    /// The fields the applicant fills in, as validated by the citizenship
+    /// form's schema. The status is set by reviewers, so it isn't included.
+    pub fn form_data(&self) -> serde_json::Result<Value> {
+        let mut data = serde_json::to_value(self)?;
+        if let Some(data) = data.as_object_mut() {
+            data.remove("citizenship_status");
+        }
+
+        Ok(data)
+    }
+}
+
+/// Checks the application against the citizenship form's schema, and the
+/// rules the schema can't express, e.g. that names aren't only whitespace
+fn validate_citizenship_application(app: &CitizenshipApplication) -> async_graphql::Result<()> {
+    let mut field_errors = CitizenshipApplicationDraft::from(app.clone()).field_errors();
+
+    let schema = forms::compile_form_schema(&forms::citizenship_form().schema)?;
+    for error in forms::form_field_errors(&schema, &app.form_data()?) {
+        if !field_errors
+            .iter()
+            .any(|existing| existing.field == error.field)
+        {
+            field_errors.push(error);
+        }
+    }
+
+    match field_errors.is_empty() {
+        true => Ok(()),
+        false => Err(invalid_application_error(&field_errors)),
+    }
+}
+
+pub async fn validate_application(
+    db: &DatabaseConnection,
+    user: &User,
+    app: &ApplicationKind,
+) -> async_graphql::Result<()> {
+    match app {
+        ApplicationKind::Citizenship(app) => {
+            validate_citizenship_application(app)?;
+            eligibility::ensure_citizenship_eligibility(db, user, app).await
+        }
+        ApplicationKind::Form(app) => {
+            let field_errors = forms::form_application_field_errors(
+                db,
+                &app.application_type,
+                app.form_version,
+                &app.data,
+            )
+            .await?;
+
+            match field_errors.is_empty() {
+                true => Ok(()),
+                false => Err(invalid_application_error(&field_errors)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CitizenshipStatus::*;
+
+    #[test]
+    fn pending_can_be_decided() {
+        assert!(Pending.can_transition_to(Approved));
+        assert!(Pending.can_transition_to(Rejected));
+        assert!(Pending.can_transition_to(InformationRequested));
+    }
+
+    #[test]
+    fn information_requested_can_be_decided() {
+        assert!(InformationRequested.can_transition_to(Approved));
+        assert!(InformationRequested.can_transition_to(Rejected));
+        assert!(!InformationRequested.can_transition_to(InformationRequested));
+    }
+
+    #[test]
+    fn decisions_are_final() {
+        for status in [Pending, InformationRequested, Approved, Rejected] {
+            assert!(!Approved.can_transition_to(status));
+            assert!(!Rejected.can_transition_to(status));
         }
-        _ => Err(new_err(
-            "INVALID_APPLICATION_TYPE",
-            "Invalid application type",
-        )),
-        // this shouldn't be needed after we make application into an enum
     }
 }
@@ -0,0 +1,172 @@
+//! Reusing assessments of identical answers.
+//!
+//! Learners often resubmit the same answer, and grading it again would cost
+//! another model request for the same result. Attempts keep a hash of
+//! everything sent to the model, so a recent attempt with the same hash can
+//! be reused instead. The hash includes [`PROMPT_VERSION`], so changing the
+//! prompt invalidates every cached assessment.
+//!
+//! Once any assessment of an answer has been flagged or reviewed by a
+//! teacher, the model may have got it wrong, so that answer is always graded
+//! again.
+
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, QueryFilter, QueryOrder, Statement,
+};
+use sha2::{Digest, Sha256};
+
+use super::{AssessmentRequest, AssessmentResult, PROMPT_VERSION};
+use crate::{ai_usage::TokenUsage, schema::question_attempts, util::variables::SECRET_VARIABLES};
+
+/// Identifies the assessment of an answer, for a model and prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    pub hash: String,
+    pub prompt_version: i32,
+}
+
+/// Ignores differences in whitespace, which don't change what the answer
+/// says. Case is kept, since it can, e.g. in chemical symbols or code.
+fn normalise_answer(answer: &str) -> String {
+    answer.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl CacheKey {
+    pub fn new(request: &AssessmentRequest, model: &str) -> Self {
+        // a JSON array, so no field can run into the next
+        let fields = serde_json::json!([
+            request.course_slug,
+            request.unit_slug,
+            request.question,
+            request.question_context,
+            request.reference_answer,
+            request.rubric,
+            normalise_answer(&request.answer),
+            model,
+            PROMPT_VERSION,
+        ]);
+
+        Self {
+            hash: hex::encode(Sha256::digest(fields.to_string())),
+            prompt_version: PROMPT_VERSION,
+        }
+    }
+}
+
+/// Whether a teacher has been asked to review, or has reviewed, any
+/// assessment of an answer with the same key
+async fn disputed<C: ConnectionTrait>(db: &C, key: &CacheKey) -> Result<bool, sea_orm::DbErr> {
+    let statement = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM question_attempts
+            LEFT JOIN question_assessments
+                ON question_assessments.user_id = question_attempts.user_id
+                AND question_assessments.course_slug = question_attempts.course_slug
+                AND question_assessments.unit_slug = question_attempts.unit_slug
+                AND question_assessments.question_slug = question_attempts.question_slug
+                AND question_assessments.attempt = question_attempts.attempt
+            WHERE question_attempts.answer_hash = $1
+                AND (
+                    question_attempts.reviewed_at IS NOT NULL
+                    OR question_assessments.flagged_at IS NOT NULL
+                    OR question_assessments.reviewed_at IS NOT NULL
+                )
+        ) AS disputed
+        "#,
+        [key.hash.clone().into()],
+    );
+
+    match db.query_one(statement).await? {
+        Some(row) => row.try_get("", "disputed"),
+        None => Ok(false),
+    }
+}
+
+/// The assessment of a recent attempt with the same key, if there is one
+/// and it hasn't been disputed. Cached results don't use any tokens.
+pub async fn cached_assessment<C: ConnectionTrait>(
+    db: &C,
+    key: &CacheKey,
+) -> Result<Option<AssessmentResult>, sea_orm::DbErr> {
+    if SECRET_VARIABLES.assessment_cache_hours <= 0 {
+        return Ok(None);
+    }
+
+    if disputed(db, key).await? {
+        return Ok(None);
+    }
+
+    let since = Utc::now() - Duration::hours(SECRET_VARIABLES.assessment_cache_hours);
+
+    Ok(question_attempts::Entity::find()
+        .filter(question_attempts::Column::AnswerHash.eq(key.hash.clone()))
+        .filter(question_attempts::Column::CreatedAt.gte(since))
+        .order_by_desc(question_attempts::Column::CreatedAt)
+        .one(db)
+        .await?
+        .map(|attempt| AssessmentResult {
+            assessment: attempt.assessment,
+            feedback: attempt.feedback,
+            rubric_scores: attempt.rubric_scores,
+            usage: TokenUsage::default(),
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(answer: &str) -> AssessmentRequest {
+        AssessmentRequest {
+            course_slug: "intro".to_string(),
+            unit_slug: "first".to_string(),
+            question: "What is 1+1?".to_string(),
+            question_context: None,
+            reference_answer: None,
+            answer: answer.to_string(),
+            rubric: None,
+            user: "john".to_string(),
+        }
+    }
+
+    #[test]
+    fn identical_answers_share_a_key() {
+        let key = CacheKey::new(&request("One plus one is two"), "gpt-4");
+
+        assert_eq!(
+            key,
+            CacheKey::new(&request("  One plus\none is  two "), "gpt-4")
+        );
+        assert_eq!(
+            key,
+            CacheKey::new(
+                &AssessmentRequest {
+                    user: "jane".to_string(),
+                    ..request("One plus one is two")
+                },
+                "gpt-4"
+            )
+        );
+
+        assert_ne!(key, CacheKey::new(&request("One plus one is 2"), "gpt-4"));
+        assert_ne!(key, CacheKey::new(&request("one plus one is two"), "gpt-4"));
+        assert_ne!(
+            key,
+            CacheKey::new(&request("One plus one is two"), "gpt-3.5")
+        );
+        assert_ne!(
+            key,
+            CacheKey::new(
+                &AssessmentRequest {
+                    question_context: Some("In base 2".to_string()),
+                    ..request("One plus one is two")
+                },
+                "gpt-4"
+            )
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use async_graphql::async_trait::async_trait;
+use openai::chat::ChatCompletionFunctionCall;
+use serde::Deserialize;
+
+use super::{
+    assessment_function, assessment_messages, parse_assessment,
+    tutor::{tutor_messages, TutorReply, TutorRequest},
+    AssessmentProvider, AssessmentRequest, AssessmentResult, ASSESSMENT_FUNCTION,
+};
+use crate::{ai_usage::TokenUsage, error::new_err};
+
+/// Local models can be slow, but a request shouldn't hang forever
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Assesses answers with an OpenAI-compatible chat completions endpoint,
+/// such as a model hosted with llama.cpp, vLLM or Ollama
+pub struct HttpProvider {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatCompletionChoice>,
+    /// Not every server reports how many tokens were used
+    usage: Option<ChatCompletionUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionUsage {
+    prompt_tokens: i32,
+    completion_tokens: i32,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionChoice {
+    message: ChatCompletionResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponseMessage {
+    content: Option<String>,
+    function_call: Option<ChatCompletionFunctionCall>,
+}
+
+impl HttpProvider {
+    /// `base_url` is the API root, e.g. `http://localhost:8080/v1`
+    pub fn new(base_url: &str, api_key: Option<String>, model: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()?,
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            api_key,
+            model: model.to_string(),
+        })
+    }
+
+    /// Sends a chat completion request, returning the first choice's
+    /// message and the tokens used
+    async fn complete(
+        &self,
+        body: serde_json::Value,
+    ) -> async_graphql::Result<(Option<ChatCompletionResponseMessage>, TokenUsage)> {
+        let mut http_request = self.client.post(&self.url).json(&body);
+
+        if let Some(api_key) = &self.api_key {
+            http_request = http_request.bearer_auth(api_key);
+        }
+
+        let response = http_request
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ChatCompletionResponse>()
+            .await?;
+
+        let usage = TokenUsage {
+            model: self.model.clone(),
+            prompt_tokens: response
+                .usage
+                .as_ref()
+                .map_or(0, |usage| usage.prompt_tokens),
+            completion_tokens: response
+                .usage
+                .as_ref()
+                .map_or(0, |usage| usage.completion_tokens),
+        };
+
+        let message = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message);
+
+        Ok((message, usage))
+    }
+}
+
+#[async_trait]
+impl AssessmentProvider for HttpProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn assess(&self, request: &AssessmentRequest) -> async_graphql::Result<AssessmentResult> {
+        let (message, usage) = self
+            .complete(serde_json::json!({
+                "model": self.model,
+                "messages": assessment_messages(request),
+                "functions": [assessment_function(request.rubric.as_ref())],
+                "function_call": { "name": ASSESSMENT_FUNCTION },
+                "user": request.user,
+            }))
+            .await?;
+
+        let message = message.ok_or_else(|| {
+            new_err(
+                "MISSING_FUNCTION_CALL",
+                "The assessment model did not return a response",
+            )
+        })?;
+
+        // some servers don't support function calling, but models asked for
+        // it usually reply with the arguments as the message instead
+        let arguments = match (message.function_call, message.content) {
+            (Some(function_call), _) => function_call.arguments,
+            (None, Some(content)) => content,
+            (None, None) => {
+                return Err(new_err(
+                    "MISSING_FUNCTION_CALL",
+                    "The assessment model did not return a function call in the response",
+                ))
+            }
+        };
+
+        Ok(parse_assessment(&arguments, request.rubric.as_ref())?.with_usage(usage))
+    }
+
+    async fn tutor(&self, request: &TutorRequest) -> async_graphql::Result<TutorReply> {
+        let (message, usage) = self
+            .complete(serde_json::json!({
+                "model": self.model,
+                "messages": tutor_messages(request),
+                "user": request.user,
+            }))
+            .await?;
+
+        let content = message
+            .and_then(|message| message.content)
+            .ok_or_else(|| new_err("MISSING_REPLY", "The tutor model did not return a reply"))?;
+
+        Ok(TutorReply { content, usage })
+    }
+}
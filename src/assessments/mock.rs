@@ -0,0 +1,186 @@
+use async_graphql::async_trait::async_trait;
+
+use super::{
+    rubric::GradedCriterion,
+    stream::FeedbackSender,
+    tutor::{TutorReply, TutorRequest},
+    AssessmentProvider, AssessmentRequest, AssessmentResult,
+};
+use crate::{ai_usage::TokenUsage, schema::sea_orm_active_enums::Assessment};
+
+/// Answers shorter than this many words fail
+const MIN_WORDS: usize = 3;
+/// Answers shorter than this many words only soft pass
+const PASS_WORDS: usize = 10;
+
+/// Grades answers by their length alone, so that tests and offline
+/// development get the same result for the same answer every time.
+///
+/// Each rubric criterion gets the same share of its maximum score as the
+/// answer has of `PASS_WORDS`.
+pub struct MockProvider;
+
+#[async_trait]
+impl AssessmentProvider for MockProvider {
+    fn model(&self) -> &str {
+        "mock"
+    }
+
+    async fn assess(&self, request: &AssessmentRequest) -> async_graphql::Result<AssessmentResult> {
+        let words = request.answer.split_whitespace().count();
+
+        if let (Some(rubric), true) = (&request.rubric, words > 0) {
+            let criteria = rubric
+                .criteria
+                .iter()
+                .map(|criterion| GradedCriterion {
+                    criterion: criterion.name.clone(),
+                    score: criterion.max_score() * words.min(PASS_WORDS) as i32 / PASS_WORDS as i32,
+                    feedback: format!("Your answer scored on {}.", criterion.name),
+                })
+                .collect();
+
+            // the assessment is derived from the rubric's score instead
+            return Ok(AssessmentResult::graded(
+                Assessment::Fail,
+                "Your answer was graded against the rubric.".to_string(),
+                Some(rubric),
+                criteria,
+            )?
+            .with_usage(mock_usage(request)));
+        }
+
+        let (assessment, feedback) = match words {
+            0 => (Assessment::Unknown, "You didn't answer the question."),
+            words if words < MIN_WORDS => (
+                Assessment::Fail,
+                "Your answer is too short. Explain your reasoning in a full sentence.",
+            ),
+            words if words < PASS_WORDS => (
+                Assessment::SoftPass,
+                "You're on the right track, but **add more detail** to your answer.",
+            ),
+            _ => (Assessment::Pass, "Well done, that's a thorough answer."),
+        };
+
+        Ok(AssessmentResult {
+            assessment,
+            feedback: feedback.to_string(),
+            rubric_scores: None,
+            usage: mock_usage(request),
+        })
+    }
+
+    /// Sends the feedback a word at a time
+    async fn assess_streaming(
+        &self,
+        request: &AssessmentRequest,
+        feedback: &FeedbackSender,
+    ) -> async_graphql::Result<AssessmentResult> {
+        let result = self.assess(request).await?;
+        for word in result.feedback.split_inclusive(' ') {
+            let _ = feedback.send(word.to_string());
+        }
+
+        Ok(result)
+    }
+
+    /// Points the learner back at their feedback
+    async fn tutor(&self, request: &TutorRequest) -> async_graphql::Result<TutorReply> {
+        let prompt_tokens = request
+            .messages
+            .iter()
+            .map(|(_, content)| content.split_whitespace().count())
+            .sum::<usize>();
+
+        Ok(TutorReply {
+            content: format!(
+                "Have another look at the feedback on your answer: {}",
+                request.feedback
+            ),
+            usage: TokenUsage {
+                model: "mock".to_string(),
+                prompt_tokens: prompt_tokens as i32,
+                completion_tokens: 10,
+            },
+        })
+    }
+}
+
+/// Counts words as tokens, so usage can be tested without a model
+fn mock_usage(request: &AssessmentRequest) -> TokenUsage {
+    TokenUsage {
+        model: "mock".to_string(),
+        prompt_tokens: (request.question.split_whitespace().count()
+            + request.answer.split_whitespace().count()) as i32,
+        completion_tokens: 10,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::rubric::{Rubric, RubricCriterion, RubricDescriptor},
+        *,
+    };
+
+    async fn assess_with(answer: &str, rubric: Option<Rubric>) -> AssessmentResult {
+        MockProvider
+            .assess(&AssessmentRequest {
+                course_slug: "intro".to_string(),
+                unit_slug: "first".to_string(),
+                question: "Why?".to_string(),
+                question_context: None,
+                reference_answer: None,
+                answer: answer.to_string(),
+                rubric,
+                user: "john".to_string(),
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn assess(answer: &str) -> Assessment {
+        assess_with(answer, None).await.assessment
+    }
+
+    #[tokio::test]
+    async fn grades_answers_by_length() {
+        assert_eq!(assess("  ").await, Assessment::Unknown);
+        assert_eq!(assess("2").await, Assessment::Fail);
+        assert_eq!(assess("because it is").await, Assessment::SoftPass);
+        assert_eq!(
+            assess("because the sum of one and one is two in base ten").await,
+            Assessment::Pass
+        );
+    }
+
+    #[tokio::test]
+    async fn scores_rubric_criteria_by_length() {
+        let rubric = Rubric {
+            criteria: vec![RubricCriterion {
+                name: "accuracy".to_string(),
+                weight: 1,
+                descriptors: vec![
+                    RubricDescriptor {
+                        score: 0,
+                        description: "Wrong".to_string(),
+                    },
+                    RubricDescriptor {
+                        score: 4,
+                        description: "Right".to_string(),
+                    },
+                ],
+            }],
+        };
+
+        // 6 of 10 words scores 2 of 4
+        let result = assess_with("one and one make two together", Some(rubric.clone())).await;
+        assert_eq!(result.assessment, Assessment::SoftPass);
+        assert_eq!(result.rubric_scores.unwrap().criteria[0].score, 2);
+
+        let result = assess_with("", Some(rubric)).await;
+        assert_eq!(result.assessment, Assessment::Unknown);
+        assert_eq!(result.rubric_scores, None);
+    }
+}
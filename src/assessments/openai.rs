@@ -0,0 +1,156 @@
+use async_graphql::async_trait::async_trait;
+use openai::chat::{ChatCompletion, ChatCompletionDelta};
+
+use super::{
+    assessment_function, assessment_messages, parse_assessment,
+    stream::{take_event_data, FeedbackSender, PartialFeedback},
+    tutor::{tutor_messages, TutorReply, TutorRequest},
+    AssessmentProvider, AssessmentRequest, AssessmentResult, ASSESSMENT_FUNCTION,
+};
+use crate::{ai_usage::TokenUsage, error::new_err};
+
+/// Streamed requests are sent directly, since the `openai` crate can't ask
+/// for the stream to report usage
+const CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+/// Assesses answers with the OpenAI API, using the key set by `OPENAI_KEY`
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(model: &str, api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AssessmentProvider for OpenAiProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn assess(&self, request: &AssessmentRequest) -> async_graphql::Result<AssessmentResult> {
+        let response = ChatCompletion::builder(&self.model, assessment_messages(request))
+            .functions([assessment_function(request.rubric.as_ref())])
+            .function_call(serde_json::json!({ "name": ASSESSMENT_FUNCTION }))
+            .user(request.user.clone())
+            .create()
+            .await?;
+
+        let function_call = response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.function_call.as_ref())
+            .ok_or_else(|| {
+                new_err(
+                    "MISSING_FUNCTION_CALL",
+                    "OpenAI did not return a function call in the response",
+                )
+            })?;
+
+        let usage = TokenUsage {
+            model: self.model.clone(),
+            prompt_tokens: response.usage.map_or(0, |usage| usage.prompt_tokens as i32),
+            completion_tokens: response
+                .usage
+                .map_or(0, |usage| usage.completion_tokens as i32),
+        };
+
+        Ok(parse_assessment(&function_call.arguments, request.rubric.as_ref())?.with_usage(usage))
+    }
+
+    async fn assess_streaming(
+        &self,
+        request: &AssessmentRequest,
+        feedback: &FeedbackSender,
+    ) -> async_graphql::Result<AssessmentResult> {
+        let mut response = self
+            .client
+            .post(CHAT_COMPLETIONS_URL)
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "messages": assessment_messages(request),
+                "functions": [assessment_function(request.rubric.as_ref())],
+                "function_call": { "name": ASSESSMENT_FUNCTION },
+                "user": request.user,
+                "stream": true,
+                // the last event then reports the tokens used
+                "stream_options": { "include_usage": true },
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let mut buffer = Vec::new();
+        let mut arguments = String::new();
+        let mut partial_feedback = PartialFeedback::default();
+        let mut usage = None;
+        while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+
+            for data in take_event_data(&mut buffer) {
+                let delta = serde_json::from_str::<ChatCompletionDelta>(&data)?;
+                usage = delta.usage.or(usage);
+
+                for choice in delta.choices {
+                    let Some(part) = choice.delta.function_call.and_then(|call| call.arguments)
+                    else {
+                        continue;
+                    };
+
+                    arguments.push_str(&part);
+                    if let Some(unsent) = partial_feedback.next(&arguments) {
+                        let _ = feedback.send(unsent);
+                    }
+                }
+            }
+        }
+
+        if arguments.is_empty() {
+            return Err(new_err(
+                "MISSING_FUNCTION_CALL",
+                "OpenAI did not return a function call in the response",
+            ));
+        }
+
+        let usage = TokenUsage {
+            model: self.model.clone(),
+            prompt_tokens: usage.map_or(0, |usage| usage.prompt_tokens as i32),
+            completion_tokens: usage.map_or(0, |usage| usage.completion_tokens as i32),
+        };
+
+        Ok(parse_assessment(&arguments, request.rubric.as_ref())?.with_usage(usage))
+    }
+
+    async fn tutor(&self, request: &TutorRequest) -> async_graphql::Result<TutorReply> {
+        let response = ChatCompletion::builder(&self.model, tutor_messages(request))
+            .user(request.user.clone())
+            .create()
+            .await?;
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .ok_or_else(|| new_err("MISSING_REPLY", "OpenAI did not return a reply"))?;
+
+        Ok(TutorReply {
+            content,
+            usage: TokenUsage {
+                model: self.model.clone(),
+                prompt_tokens: response.usage.map_or(0, |usage| usage.prompt_tokens as i32),
+                completion_tokens: response
+                    .usage
+                    .map_or(0, |usage| usage.completion_tokens as i32),
+            },
+        })
+    }
+}
@@ -0,0 +1,291 @@
+//! Rubrics let course authors say how answers to a question are graded.
+//!
+//! Each criterion is scored separately against its descriptors, and the
+//! overall [`Assessment`] is derived from the weighted score, so that every
+//! question uses the same pass marks.
+
+use std::collections::HashSet;
+
+use async_graphql::{InputObject, SimpleObject};
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::new_err, schema::sea_orm_active_enums::Assessment};
+
+/// Answers scoring at least this percentage pass
+pub const PASS_PERCENT: i32 = 80;
+/// Answers scoring at least this percentage soft pass
+pub const SOFT_PASS_PERCENT: i32 = 50;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    FromJsonQueryResult,
+    SimpleObject,
+    InputObject,
+)]
+#[graphql(rename_fields = "snake_case", input_name = "RubricInput")]
+pub struct Rubric {
+    pub criteria: Vec<RubricCriterion>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(rename_fields = "snake_case", input_name = "RubricCriterionInput")]
+pub struct RubricCriterion {
+    pub name: String,
+    /// How much the criterion counts towards the overall score, relative
+    /// to the other criteria
+    pub weight: i32,
+    /// What an answer has to do to get each score
+    pub descriptors: Vec<RubricDescriptor>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(rename_fields = "snake_case", input_name = "RubricDescriptorInput")]
+pub struct RubricDescriptor {
+    pub score: i32,
+    pub description: String,
+}
+
+/// How an answer scored against a rubric
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, SimpleObject,
+)]
+#[graphql(rename_fields = "snake_case")]
+pub struct RubricScores {
+    /// The weighted score out of 100, rounded down
+    pub percent: i32,
+    pub criteria: Vec<CriterionScore>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CriterionScore {
+    pub criterion: String,
+    pub score: i32,
+    pub max_score: i32,
+    /// Markdown feedback on this criterion
+    pub feedback: String,
+}
+
+/// A criterion's score, as returned by a grader
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct GradedCriterion {
+    pub criterion: String,
+    pub score: i32,
+    pub feedback: String,
+}
+
+impl RubricCriterion {
+    pub fn max_score(&self) -> i32 {
+        self.descriptors
+            .iter()
+            .map(|descriptor| descriptor.score)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn invalid_rubric(message: &str) -> async_graphql::Error {
+    new_err("INVALID_RUBRIC", message)
+}
+
+/// Checks a rubric can be graded against, failing with `INVALID_RUBRIC`
+pub fn validate_rubric(rubric: &Rubric) -> async_graphql::Result<()> {
+    if rubric.criteria.is_empty() {
+        return Err(invalid_rubric("A rubric needs at least one criterion"));
+    }
+
+    let mut names = HashSet::new();
+    for criterion in &rubric.criteria {
+        if criterion.name.trim().is_empty() {
+            return Err(invalid_rubric("Criteria must have a name"));
+        }
+        if !names.insert(criterion.name.as_str()) {
+            return Err(invalid_rubric(&format!(
+                "There is more than one criterion named {:?}",
+                criterion.name
+            )));
+        }
+        if criterion.weight <= 0 {
+            return Err(invalid_rubric(&format!(
+                "The weight of {:?} must be positive",
+                criterion.name
+            )));
+        }
+
+        let mut scores = HashSet::new();
+        for descriptor in &criterion.descriptors {
+            if descriptor.score < 0 || !scores.insert(descriptor.score) {
+                return Err(invalid_rubric(&format!(
+                    "The descriptors of {:?} must have different scores of 0 or more",
+                    criterion.name
+                )));
+            }
+        }
+        if criterion.max_score() == 0 {
+            return Err(invalid_rubric(&format!(
+                "{:?} needs a descriptor with a score above 0",
+                criterion.name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// The overall assessment for a weighted score out of 100
+pub fn assessment_for_percent(percent: i32) -> Assessment {
+    match percent {
+        percent if percent >= PASS_PERCENT => Assessment::Pass,
+        percent if percent >= SOFT_PASS_PERCENT => Assessment::SoftPass,
+        _ => Assessment::Fail,
+    }
+}
+
+/// Combines a grader's scores into the rubric's weighted score. Every
+/// criterion must be scored exactly once, within its descriptors' range.
+pub fn score_rubric(
+    rubric: &Rubric,
+    graded: Vec<GradedCriterion>,
+) -> async_graphql::Result<RubricScores> {
+    let invalid = |message: String| new_err("INVALID_ARGUMENTS", &message);
+
+    if let Some(unknown) = graded
+        .iter()
+        .find(|graded| !rubric.criteria.iter().any(|c| c.name == graded.criterion))
+    {
+        return Err(invalid(format!(
+            "The grader scored an unknown criterion {:?}",
+            unknown.criterion
+        )));
+    }
+
+    let mut criteria = Vec::new();
+    let mut weighted = 0;
+    let mut total_weight = 0;
+
+    for criterion in &rubric.criteria {
+        let mut scores = graded.iter().filter(|g| g.criterion == criterion.name);
+        let (Some(graded), None) = (scores.next(), scores.next()) else {
+            return Err(invalid(format!(
+                "The grader must score {:?} exactly once",
+                criterion.name
+            )));
+        };
+
+        let max_score = criterion.max_score();
+        if !(0..=max_score).contains(&graded.score) {
+            return Err(invalid(format!(
+                "The grader scored {:?} {}, but it is out of {}",
+                criterion.name, graded.score, max_score
+            )));
+        }
+
+        // scaled so criteria with more descriptors don't count for more
+        weighted +=
+            i64::from(criterion.weight) * i64::from(graded.score) * 100 / i64::from(max_score);
+        total_weight += i64::from(criterion.weight);
+
+        criteria.push(CriterionScore {
+            criterion: criterion.name.clone(),
+            score: graded.score,
+            max_score,
+            feedback: graded.feedback.clone(),
+        });
+    }
+
+    Ok(RubricScores {
+        percent: (weighted / total_weight.max(1)) as i32,
+        criteria,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn criterion(name: &str, weight: i32, max_score: i32) -> RubricCriterion {
+        RubricCriterion {
+            name: name.to_string(),
+            weight,
+            descriptors: (0..=max_score)
+                .map(|score| RubricDescriptor {
+                    score,
+                    description: format!("{} out of {}", score, max_score),
+                })
+                .collect(),
+        }
+    }
+
+    fn graded(criterion: &str, score: i32) -> GradedCriterion {
+        GradedCriterion {
+            criterion: criterion.to_string(),
+            score,
+            feedback: String::new(),
+        }
+    }
+
+    #[test]
+    fn validates_rubrics() {
+        let rubric = Rubric {
+            criteria: vec![criterion("accuracy", 2, 4), criterion("clarity", 1, 2)],
+        };
+        assert!(validate_rubric(&rubric).is_ok());
+
+        assert!(validate_rubric(&Rubric { criteria: vec![] }).is_err());
+        assert!(validate_rubric(&Rubric {
+            criteria: vec![criterion("accuracy", 1, 2), criterion("accuracy", 1, 2)],
+        })
+        .is_err());
+        assert!(validate_rubric(&Rubric {
+            criteria: vec![criterion("accuracy", 0, 2)],
+        })
+        .is_err());
+        assert!(validate_rubric(&Rubric {
+            criteria: vec![criterion("accuracy", 1, 0)],
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn weights_criteria_scores() {
+        let rubric = Rubric {
+            criteria: vec![criterion("accuracy", 3, 4), criterion("clarity", 1, 2)],
+        };
+
+        // (3 * 4/4 + 1 * 0/2) / 4
+        let scores =
+            score_rubric(&rubric, vec![graded("clarity", 0), graded("accuracy", 4)]).unwrap();
+        assert_eq!(scores.percent, 75);
+        assert_eq!(scores.criteria[0].criterion, "accuracy");
+        assert_eq!(scores.criteria[1].max_score, 2);
+        assert_eq!(assessment_for_percent(scores.percent), Assessment::SoftPass);
+
+        assert_eq!(assessment_for_percent(80), Assessment::Pass);
+        assert_eq!(assessment_for_percent(49), Assessment::Fail);
+    }
+
+    #[test]
+    fn rejects_incomplete_grading() {
+        let rubric = Rubric {
+            criteria: vec![criterion("accuracy", 1, 4), criterion("clarity", 1, 2)],
+        };
+
+        assert!(score_rubric(&rubric, vec![graded("accuracy", 4)]).is_err());
+        assert!(score_rubric(&rubric, vec![graded("accuracy", 5), graded("clarity", 1)]).is_err());
+        assert!(score_rubric(
+            &rubric,
+            vec![
+                graded("accuracy", 1),
+                graded("clarity", 1),
+                graded("style", 1)
+            ]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! Streaming feedback to learners while their answer is being graded.
+//!
+//! Models write their assessment as the JSON arguments of a function call,
+//! so the feedback is read out of the arguments as they arrive.
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Receives feedback in pieces, as it is written
+pub type FeedbackSender = UnboundedSender<String>;
+
+/// Decodes a JSON string starting after its opening quote. Returns the text
+/// so far, and whether the closing quote was reached. Stops before an
+/// escape sequence that hasn't fully arrived yet.
+fn decode_string(json: &str) -> (String, Option<usize>) {
+    let mut text = String::new();
+    let mut chars = json.char_indices();
+    // the first half of a surrogate pair, waiting for the second
+    let mut high_surrogate = None;
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return (text, Some(index + 1)),
+            '\\' => {
+                let Some((_, escape)) = chars.next() else {
+                    break;
+                };
+                let decoded = match escape {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                        let Ok(unit) = u16::from_str_radix(&hex, 16) else {
+                            break;
+                        };
+                        if hex.len() < 4 {
+                            break;
+                        }
+
+                        let units = match high_surrogate.take() {
+                            Some(high) => vec![high, unit],
+                            None if (0xd800..0xdc00).contains(&unit) => {
+                                high_surrogate = Some(unit);
+                                continue;
+                            }
+                            None => vec![unit],
+                        };
+                        text.extend(
+                            char::decode_utf16(units)
+                                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+                        );
+                        continue;
+                    }
+                    other => other,
+                };
+                text.push(decoded);
+            }
+            c => text.push(c),
+        }
+    }
+
+    (text, None)
+}
+
+/// The top level `field` of a JSON object that may not have fully arrived,
+/// if its string value has started
+fn partial_string_field(json: &str, field: &str) -> Option<String> {
+    let mut depth = 0;
+    let mut index = 0;
+
+    while let Some(c) = json[index..].chars().next() {
+        match c {
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            '"' => {
+                let (key, end) = decode_string(&json[index + 1..]);
+                let end = index + 1 + end?;
+
+                let rest = json[end..].trim_start();
+                if depth == 1 && key == field && rest.starts_with(':') {
+                    let value = rest[1..].trim_start();
+                    return value.strip_prefix('"').map(|value| decode_string(value).0);
+                }
+
+                index = end;
+                continue;
+            }
+            _ => {}
+        }
+        index += c.len_utf8();
+    }
+
+    None
+}
+
+/// Tracks how much of the feedback has been sent
+#[derive(Debug, Default)]
+pub struct PartialFeedback {
+    sent: usize,
+}
+
+impl PartialFeedback {
+    /// The feedback in `arguments` that hasn't been sent yet, given all of
+    /// the function call's arguments so far
+    pub fn next(&mut self, arguments: &str) -> Option<String> {
+        let feedback = partial_string_field(arguments, "feedback")?;
+        let unsent = feedback
+            .get(self.sent..)
+            .filter(|unsent| !unsent.is_empty())?;
+        self.sent = feedback.len();
+
+        Some(unsent.to_string())
+    }
+}
+
+/// Takes the data of each complete server-sent event line received from a
+/// model so far, leaving a partial line in `buffer` for the next chunk.
+/// The `[DONE]` marker that ends the stream is skipped.
+pub fn take_event_data(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut data = Vec::new();
+
+    while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+        let line = buffer.drain(..=end).collect::<Vec<_>>();
+        let line = String::from_utf8_lossy(&line);
+
+        match line.trim().strip_prefix("data:").map(str::trim) {
+            Some("[DONE]") | None => {}
+            Some(event) => data.push(event.to_string()),
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_partial_feedback() {
+        let arguments = r#"{"assessment": "PASS", "criteria": [{"criterion": "a", "feedback": "no"}], "feedback": "Well \"done\"\n🎉 \ud83c\udf89 é"}"#;
+        let mut feedback = PartialFeedback::default();
+
+        let streamed = (1..=arguments.len())
+            .filter(|end| arguments.is_char_boundary(*end))
+            .filter_map(|end| feedback.next(&arguments[..end]))
+            .collect::<Vec<_>>();
+
+        assert!(streamed.len() > 1);
+        assert_eq!(streamed.concat(), "Well \"done\"\n🎉 🎉 é");
+    }
+
+    #[test]
+    fn waits_for_the_feedback_value() {
+        let mut feedback = PartialFeedback::default();
+
+        assert_eq!(feedback.next(r#"{"feedb"#), None);
+        assert_eq!(feedback.next(r#"{"feedback": "#), None);
+        assert_eq!(
+            feedback.next(r#"{"feedback": "Good"#),
+            Some("Good".to_string())
+        );
+        assert_eq!(feedback.next(r#"{"feedback": "Good\"#), None);
+        assert_eq!(
+            feedback.next(r#"{"feedback": "Good\n"#),
+            Some("\n".to_string())
+        );
+    }
+
+    #[test]
+    fn takes_complete_event_lines() {
+        let mut buffer = b"data: {\"a\": 1}\n\ndata: {\"b\"".to_vec();
+        assert_eq!(take_event_data(&mut buffer), [r#"{"a": 1}"#]);
+
+        buffer.extend_from_slice(b": \"\xc3");
+        assert!(take_event_data(&mut buffer).is_empty());
+
+        buffer.extend_from_slice(b"\xa9\"}\r\n\ndata: [DONE]\n\n");
+        assert_eq!(take_event_data(&mut buffer), [r#"{"b": "é"}"#]);
+        assert!(buffer.is_empty());
+    }
+}
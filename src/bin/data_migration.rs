@@ -0,0 +1,31 @@
+use graph_api::data_migrations;
+use sea_orm::Database;
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_target(false)
+        .init();
+
+    let (flags, names): (Vec<String>, Vec<String>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
+    let dry_run = flags.iter().any(|flag| flag == "--dry-run");
+    if names.is_empty() || flags.iter().any(|flag| flag != "--dry-run") {
+        anyhow::bail!(
+            "Usage: data_migration [--dry-run] <name>..., available migrations: {:?}",
+            data_migrations::MIGRATIONS
+        );
+    }
+
+    let db = Database::connect(dotenv::var("DATABASE_URL")?).await?;
+
+    for name in names {
+        tracing::info!("Running data migration {}", name);
+        data_migrations::run(&db, &name, dry_run).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Serves the API over plain HTTP, streaming server-sent events as they
+//! happen. Lambda buffers responses, so subscriptions only stream through
+//! this server.
+
+use std::{convert::Infallible, net::SocketAddr};
+
+use graph_api::{wants_event_stream, App};
+use hyper::{
+    body::to_bytes,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use lambda_http::Error;
+
+async fn handle(app: App, request: Request<Body>) -> Result<Response<Body>, Error> {
+    let (parts, body) = request.into_parts();
+    let event = lambda_http::Request::from_parts(parts, to_bytes(body).await?.to_vec().into());
+
+    if wants_event_stream(&event) {
+        let events = app.event_stream(event).await?;
+
+        return Ok(Response::builder()
+            .status(200)
+            .header("content-type", "text/event-stream")
+            .header("cache-control", "no-cache")
+            .header("Access-Control-Allow-Origin", "*")
+            .header("Access-Control-Allow-Methods", "POST")
+            .header("Access-Control-Allow-Headers", "*")
+            .body(Body::wrap_stream(
+                async_graphql::futures_util::StreamExt::map(events, Ok::<_, Infallible>),
+            ))?);
+    }
+
+    let (parts, body) = app.respond(event).await?.into_parts();
+    Ok(Response::from_parts(parts, body.to_vec().into()))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    dotenv::dotenv().ok();
+
+    let port = match std::env::var("PORT") {
+        Ok(port) => port.parse()?,
+        Err(_) => 8000,
+    };
+    let address = SocketAddr::from(([0, 0, 0, 0], port));
+
+    let app = App::new(None).await?;
+    let make_service = make_service_fn(move |_| {
+        let app = app.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(app.clone(), request))) }
+    });
+
+    println!("Listening on http://{}", address);
+    Server::bind(&address).serve(make_service).await?;
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+pub mod credentials;
+
+use async_graphql::Enum;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ConnectionTrait, DeriveActiveEnum, EntityTrait, EnumIter, NotSet, Set,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::citizens;
+
+#[derive(
+    Enum, Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, EnumIter, DeriveActiveEnum,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+pub enum CitizenStatus {
+    #[sea_orm(string_value = "Active")]
+    Active,
+    #[sea_orm(string_value = "Suspended")]
+    Suspended,
+    #[sea_orm(string_value = "Renounced")]
+    Renounced,
+}
+
+impl CitizenStatus {
+    /// Whether a citizen may be moved from this status to `next`. Admins
+    /// suspend and reinstate citizens, and citizens can renounce while
+    /// active or suspended. Renouncing citizenship is final.
+    pub fn can_transition_to(self, next: CitizenStatus) -> bool {
+        use CitizenStatus::*;
+
+        matches!(
+            (self, next),
+            (Active, Suspended) | (Suspended, Active) | (Active | Suspended, Renounced)
+        )
+    }
+
+    /// Citizens who haven't renounced are still citizens while suspended
+    pub fn is_citizen(self) -> bool {
+        self != CitizenStatus::Renounced
+    }
+}
+
+/// Formats a citizen's sequential number as their citizenship number,
+/// e.g. `L000000422`.
+///
+/// The last digit is a Luhn check digit, so most typos can be caught
+/// without a database lookup.
+pub fn format_citizenship_number(number: i64) -> String {
+    let digits = format!("{:08}", number);
+
+    format!("L{}{}", digits, luhn_check_digit(&digits))
+}
+
+fn luhn_check_digit(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| match i % 2 {
+            // double every second digit, starting with the rightmost
+            0 if digit * 2 > 9 => digit * 2 - 9,
+            0 => digit * 2,
+            _ => digit,
+        })
+        .sum();
+
+    (10 - sum % 10) % 10
+}
+
+/// Makes the user a citizen because of the approved application.
+///
+/// Someone who renounced and later applied again keeps their original
+/// citizenship number.
+pub async fn grant_citizenship<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    application_id: Uuid,
+) -> Result<citizens::Model, sea_orm::DbErr> {
+    let now = Utc::now();
+
+    let citizen = citizens::ActiveModel {
+        user_id: Set(user_id),
+        number: NotSet,
+        application_id: Set(Some(application_id)),
+        status: Set(CitizenStatus::Active),
+        status_reason: Set(None),
+        granted_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    citizens::Entity::insert(citizen)
+        .on_conflict(
+            OnConflict::column(citizens::Column::UserId)
+                .update_columns([
+                    citizens::Column::ApplicationId,
+                    citizens::Column::Status,
+                    citizens::Column::StatusReason,
+                    citizens::Column::GrantedAt,
+                    citizens::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+}
+
+/// Returns the user's citizen record, if they have ever been granted citizenship
+pub async fn find_citizen<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+) -> Result<Option<citizens::Model>, sea_orm::DbErr> {
+    citizens::Entity::find_by_id(user_id).one(db).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CitizenStatus::*;
+    use super::*;
+
+    #[test]
+    fn formats_citizenship_numbers() {
+        assert_eq!(format_citizenship_number(1), "L000000018");
+        assert_eq!(format_citizenship_number(42), "L000000422");
+        assert_eq!(format_citizenship_number(12345678), "L123456782");
+    }
+
+    #[test]
+    fn suspension_can_be_reversed() {
+        assert!(Active.can_transition_to(Suspended));
+        assert!(Suspended.can_transition_to(Active));
+        assert!(!Active.can_transition_to(Active));
+    }
+
+    #[test]
+    fn citizens_can_renounce() {
+        assert!(Active.can_transition_to(Renounced));
+        assert!(Suspended.can_transition_to(Renounced));
+    }
+
+    #[test]
+    fn renouncing_is_final() {
+        for status in [Active, Suspended, Renounced] {
+            assert!(!Renounced.can_transition_to(status));
+        }
+        assert!(!Renounced.is_citizen());
+    }
+}
@@ -0,0 +1,127 @@
+//! Every answer a learner gives to a question is kept as an attempt. Their
+//! latest attempt is also kept in `question_assessments`, which is what
+//! progress and prerequisites are based on, and what teachers review.
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, Statement,
+};
+use uuid::Uuid;
+
+use crate::{
+    assessments::{cache::CacheKey, AssessmentResult},
+    schema::{question_assessments, question_attempts, unit_questions},
+};
+
+/// Records a new attempt at a question and makes it the user's current
+/// assessment.
+///
+/// Attempts are numbered under a transaction-level advisory lock on the
+/// user and question, so answers submitted at the same time get different
+/// numbers. `db` must be a transaction: outside one the lock is released
+/// straight away.
+///
+/// `cache_key` is only given for answers sent to a model, and `cached` says
+/// whether the result was reused instead.
+pub async fn record_attempt<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    question: &unit_questions::Model,
+    answer: String,
+    result: AssessmentResult,
+    cache_key: Option<CacheKey>,
+    cached: bool,
+) -> Result<question_assessments::Model, sea_orm::DbErr> {
+    let prompt_version = cache_key.as_ref().map(|key| key.prompt_version);
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT pg_advisory_xact_lock(hashtextextended($1, 0))",
+        [format!(
+            "question_attempts/{}/{}/{}/{}",
+            user_id, question.course_slug, question.unit_slug, question.slug
+        )
+        .into()],
+    ))
+    .await?;
+
+    let attempt = question_attempts::Entity::find()
+        .filter(question_attempts::Column::UserId.eq(user_id))
+        .filter(question_attempts::Column::CourseSlug.eq(question.course_slug.clone()))
+        .filter(question_attempts::Column::UnitSlug.eq(question.unit_slug.clone()))
+        .filter(question_attempts::Column::QuestionSlug.eq(question.slug.clone()))
+        .order_by_desc(question_attempts::Column::Attempt)
+        .one(db)
+        .await?
+        .map_or(1, |previous| previous.attempt + 1);
+
+    question_attempts::Model {
+        id: Uuid::new_v4(),
+        user_id,
+        course_slug: question.course_slug.clone(),
+        unit_slug: question.unit_slug.clone(),
+        question_slug: question.slug.clone(),
+        attempt,
+        answer: answer.clone(),
+        assessment: result.assessment,
+        feedback: result.feedback.clone(),
+        rubric_scores: result.rubric_scores.clone(),
+        answer_hash: cache_key.map(|key| key.hash),
+        prompt_version,
+        cached,
+        reviewed_by: None,
+        reviewed_at: None,
+        created_at: Utc::now(),
+    }
+    .into_active_model()
+    .insert(db)
+    .await?;
+
+    question_assessments::Entity::insert(
+        question_assessments::Model {
+            id: Uuid::new_v4(),
+            user_id,
+            course_slug: question.course_slug.clone(),
+            unit_slug: question.unit_slug.clone(),
+            question_slug: question.slug.clone(),
+            answer,
+            assessment: result.assessment,
+            feedback: result.feedback,
+            rubric_scores: result.rubric_scores,
+            attempt,
+            flagged_at: None,
+            flag_reason: None,
+            reviewed_by: None,
+            reviewed_at: None,
+            prompt_version,
+            updated_at: Utc::now(),
+        }
+        .into_active_model(),
+    )
+    .on_conflict(
+        OnConflict::columns([
+            question_assessments::Column::UserId,
+            question_assessments::Column::CourseSlug,
+            question_assessments::Column::UnitSlug,
+            question_assessments::Column::QuestionSlug,
+        ])
+        .update_columns([
+            question_assessments::Column::Answer,
+            question_assessments::Column::Assessment,
+            question_assessments::Column::Feedback,
+            question_assessments::Column::RubricScores,
+            question_assessments::Column::Attempt,
+            // a new attempt hasn't been flagged or reviewed yet
+            question_assessments::Column::FlaggedAt,
+            question_assessments::Column::FlagReason,
+            question_assessments::Column::ReviewedBy,
+            question_assessments::Column::ReviewedAt,
+            question_assessments::Column::PromptVersion,
+            question_assessments::Column::UpdatedAt,
+        ])
+        .to_owned(),
+    )
+    .exec_with_returning(db)
+    .await
+}
@@ -0,0 +1,250 @@
+//! Certificates issued to learners who complete every unit of a course.
+//!
+//! The learner's name and the course title are copied onto the
+//! certificate when it is issued, so it reads the same however either
+//! changes later.
+
+use std::fmt::Write;
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+};
+use uuid::Uuid;
+
+use crate::{
+    courses::progress::course_progress_summaries,
+    schema::{course_certificates, courses, users},
+};
+
+/// Where a certificate's PDF can be downloaded from, followed by
+/// `{id}.pdf`
+pub const CERTIFICATE_PDF_PATH: &str = "/certificates/";
+
+pub fn certificate_pdf_path(id: Uuid) -> String {
+    format!("{}{}.pdf", CERTIFICATE_PDF_PATH, id)
+}
+
+/// The certificate id in a download path from [`certificate_pdf_path`]
+pub fn certificate_id_from_path(path: &str) -> Option<Uuid> {
+    path.strip_prefix(CERTIFICATE_PDF_PATH)?
+        .strip_suffix(".pdf")?
+        .parse()
+        .ok()
+}
+
+async fn find_certificate<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    course_slug: &str,
+) -> Result<Option<course_certificates::Model>, sea_orm::DbErr> {
+    course_certificates::Entity::find()
+        .filter(course_certificates::Column::UserId.eq(user_id))
+        .filter(course_certificates::Column::CourseSlug.eq(course_slug))
+        .one(db)
+        .await
+}
+
+/// Issues a certificate for the course if the user has completed all of
+/// its units. Returns the existing certificate if one was already issued,
+/// including by a concurrent request.
+pub async fn issue_certificate_if_complete<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    course_slug: &str,
+) -> Result<Option<course_certificates::Model>, sea_orm::DbErr> {
+    let existing = find_certificate(db, user_id, course_slug).await?;
+
+    if existing.is_some() {
+        return Ok(existing);
+    }
+
+    let completed = course_progress_summaries(db, user_id, Some(course_slug))
+        .await?
+        .into_iter()
+        .next()
+        .is_some_and(|summary| {
+            summary.total_units > 0 && summary.completed_units == summary.total_units
+        });
+
+    if !completed {
+        return Ok(None);
+    }
+
+    let (Some(user), Some(course)) = (
+        users::Entity::find_by_id(user_id).one(db).await?,
+        courses::Entity::find_by_id(course_slug.to_string())
+            .one(db)
+            .await?,
+    ) else {
+        return Ok(None);
+    };
+
+    course_certificates::Entity::insert(
+        course_certificates::Model {
+            id: Uuid::new_v4(),
+            user_id,
+            course_slug: course.slug,
+            learner_name: format!("{} {}", user.first_name, user.last_name),
+            course_title: course.title,
+            completed_at: Utc::now(),
+            issued_at: Utc::now(),
+        }
+        .into_active_model(),
+    )
+    .on_conflict(
+        OnConflict::columns([
+            course_certificates::Column::UserId,
+            course_certificates::Column::CourseSlug,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    find_certificate(db, user_id, course_slug).await
+}
+
+/// Escapes text for a PDF string literal. The standard fonts only cover
+/// Latin-1, so anything else is replaced with `?`.
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            '\u{a0}'..='\u{ff}' => format!("\\{:o}", c as u32),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Renders a certificate as a single landscape A4 page
+pub fn certificate_pdf(certificate: &course_certificates::Model) -> Vec<u8> {
+    // (font, size, y, text), all left aligned to the same margin
+    let lines = [
+        ("F2", 28, 470, "Lumina University".to_string()),
+        ("F1", 18, 430, "Certificate of Completion".to_string()),
+        ("F1", 12, 360, "This certifies that".to_string()),
+        ("F2", 24, 325, certificate.learner_name.clone()),
+        ("F1", 12, 285, "has completed the course".to_string()),
+        ("F2", 20, 250, certificate.course_title.clone()),
+        (
+            "F1",
+            12,
+            210,
+            format!("on {}", certificate.completed_at.format("%-d %B %Y")),
+        ),
+        ("F1", 9, 100, format!("Certificate ID: {}", certificate.id)),
+    ];
+
+    let mut content = String::from("2 w 40 40 762 515 re S\n");
+    for (font, size, y, text) in lines {
+        let _ = writeln!(
+            content,
+            "BT /{} {} Tf 80 {} Td ({}) Tj ET",
+            font,
+            size,
+            y,
+            pdf_text(&text)
+        );
+    }
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 842 595] \
+         /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents 6 0 R >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ),
+    ];
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = writeln!(pdf, "{} 0 obj\n{}\nendobj", index + 1, object);
+    }
+
+    let xref = pdf.len();
+    let _ = writeln!(pdf, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = writeln!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+        objects.len() + 1,
+        xref
+    );
+
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn certificate() -> course_certificates::Model {
+        course_certificates::Model {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            course_slug: "intro".to_string(),
+            learner_name: "Zoë (Jo) Smith".to_string(),
+            course_title: "Intro to Lumina".to_string(),
+            completed_at: Utc.with_ymd_and_hms(2023, 5, 4, 12, 0, 0).unwrap(),
+            issued_at: Utc.with_ymd_and_hms(2023, 5, 4, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn round_trips_download_paths() {
+        let id = Uuid::new_v4();
+
+        assert_eq!(
+            certificate_id_from_path(&certificate_pdf_path(id)),
+            Some(id)
+        );
+        assert_eq!(certificate_id_from_path("/certificates/nope.pdf"), None);
+        assert_eq!(
+            certificate_id_from_path(&format!("/certificates/{}", id)),
+            None
+        );
+    }
+
+    #[test]
+    fn escapes_pdf_text() {
+        assert_eq!(pdf_text("Zoë (Jo)"), "Zo\\353 \\(Jo\\)");
+        assert_eq!(pdf_text("a\\b"), "a\\\\b");
+        assert_eq!(pdf_text("李"), "?");
+    }
+
+    #[test]
+    fn renders_certificate_pdf() {
+        let pdf = String::from_utf8(certificate_pdf(&certificate())).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(Zo\\353 \\(Jo\\) Smith) Tj"));
+        assert!(pdf.contains("(on 4 May 2023) Tj"));
+
+        // the cross-reference table points at each object
+        let xref = pdf.find("\nxref\n").unwrap() + 1;
+        let startxref = pdf.lines().rev().nth(1).unwrap();
+        assert_eq!(startxref.parse::<usize>().unwrap(), xref);
+        for (index, line) in pdf[xref..].lines().skip(3).take(6).enumerate() {
+            let offset = line[..10].parse::<usize>().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+}
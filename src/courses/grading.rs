@@ -0,0 +1,144 @@
+//! Grading a learner's answer to a question, from checking it is safe to
+//! send to a model to keeping the attempt.
+
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, IntoActiveModel, Set, TransactionTrait};
+
+use crate::{
+    ai_usage::{reserve_request, settle_usage, TokenUsage, QUESTION_ASSESSMENT_FEATURE},
+    assessments::{
+        cache::{cached_assessment, CacheKey},
+        injection::looks_like_prompt_injection,
+        stream::FeedbackSender,
+        AssessmentProvider, AssessmentRequest, AssessmentResult,
+    },
+    courses::{attempts::record_attempt, find_question},
+    error::new_err,
+    graphql::types::user::User,
+    schema::{question_assessments, sea_orm_active_enums::Assessment},
+};
+
+/// Longer answers would only make grading slower and more expensive
+pub const MAX_ANSWER_LENGTH: usize = 5_000;
+
+const INJECTION_FEEDBACK: &str = "Your answer looks like it contains instructions for the \
+                                  grader, so it couldn't be graded. A teacher will review it.";
+const INJECTION_FLAG_REASON: &str = "The answer looks like an attempt to instruct the grader";
+
+/// Grades the user's answer and records it as their latest attempt. The
+/// question, its context and how it is graded all come from the course,
+/// never the learner.
+///
+/// If `feedback` is given, the feedback is sent to it as it is written.
+pub async fn grade_answer(
+    db: &DatabaseConnection,
+    provider: &dyn AssessmentProvider,
+    user: &User,
+    course_slug: String,
+    unit_slug: String,
+    question_slug: String,
+    answer: String,
+    feedback: Option<&FeedbackSender>,
+) -> async_graphql::Result<question_assessments::Model> {
+    if answer.chars().count() > MAX_ANSWER_LENGTH {
+        return Err(new_err(
+            "ANSWER_TOO_LONG",
+            &format!(
+                "Answers can be at most {} characters long",
+                MAX_ANSWER_LENGTH
+            ),
+        ));
+    }
+
+    let unit_question = find_question(db, &course_slug, &unit_slug, &question_slug).await?;
+
+    // caught answers aren't sent to the model, so they don't count towards
+    // the quota
+    let injection = looks_like_prompt_injection(&answer);
+    let (result, cache_key, cached) = match injection {
+        true => {
+            if let Some(feedback) = feedback {
+                let _ = feedback.send(INJECTION_FEEDBACK.to_string());
+            }
+
+            let result = AssessmentResult {
+                assessment: Assessment::Fail,
+                feedback: INJECTION_FEEDBACK.to_string(),
+                rubric_scores: None,
+                usage: TokenUsage::default(),
+            };
+            (result, None, false)
+        }
+        false => {
+            let request = AssessmentRequest {
+                course_slug,
+                unit_slug,
+                question: unit_question.question.clone(),
+                question_context: unit_question.context.clone(),
+                reference_answer: unit_question.reference_answer.clone(),
+                answer: answer.clone(),
+                rubric: unit_question.rubric.clone(),
+                user: slug::slugify(&user.first_name),
+            };
+            let cache_key = CacheKey::new(&request, provider.model());
+
+            // reused assessments cost nothing, so they don't count towards
+            // the quota either
+            match cached_assessment(db, &cache_key).await? {
+                Some(result) => {
+                    tracing::info!(
+                        "Assessment cache hit for {}/{}/{}",
+                        unit_question.course_slug,
+                        unit_question.unit_slug,
+                        unit_question.slug
+                    );
+                    if let Some(feedback) = feedback {
+                        let _ = feedback.send(result.feedback.clone());
+                    }
+
+                    (result, Some(cache_key), true)
+                }
+                None => {
+                    tracing::info!(
+                        "Assessment cache miss for {}/{}/{}",
+                        unit_question.course_slug,
+                        unit_question.unit_slug,
+                        unit_question.slug
+                    );
+                    let usage_id =
+                        reserve_request(db, user, QUESTION_ASSESSMENT_FEATURE, provider.model())
+                            .await?;
+
+                    let result = match feedback {
+                        Some(feedback) => provider.assess_streaming(&request, feedback).await?,
+                        None => provider.assess(&request).await?,
+                    };
+                    settle_usage(db, usage_id, &result.usage).await?;
+
+                    (result, Some(cache_key), false)
+                }
+            }
+        }
+    };
+
+    let txn = db.begin().await?;
+    let mut assessment = record_attempt(
+        &txn,
+        user.id,
+        &unit_question,
+        answer,
+        result,
+        cache_key,
+        cached,
+    )
+    .await?;
+    if injection {
+        let mut active_model = assessment.into_active_model();
+        active_model.flagged_at = Set(Some(Utc::now()));
+        active_model.flag_reason = Set(Some(INJECTION_FLAG_REASON.to_string()));
+        assessment = active_model.update(&txn).await?;
+    }
+    txn.commit().await?;
+
+    Ok(assessment)
+}
@@ -0,0 +1,267 @@
+//! The course catalog.
+//!
+//! Courses are made of units, and units of questions. Each is identified by
+//! a slug, and progress and assessments refer to them by those slugs.
+//! Courses are hidden from learners until they are published.
+
+pub mod attempts;
+pub mod certificates;
+pub mod grading;
+pub mod prerequisites;
+pub mod progress;
+pub mod tutor;
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    error::new_err,
+    schema::{course_prerequisites, course_units, courses, unit_questions},
+};
+
+/// Slugs are used in URLs, so they are kept to lowercase words joined by
+/// hyphens, e.g. `intro-to-lumina`
+pub fn validate_slug(slug: &str) -> async_graphql::Result<()> {
+    match !slug.is_empty() && slug::slugify(slug) == slug {
+        true => Ok(()),
+        false => Err(new_err(
+            "INVALID_SLUG",
+            &format!(
+                "{:?} is not a valid slug, try {:?}",
+                slug,
+                slug::slugify(slug)
+            ),
+        )),
+    }
+}
+
+/// Courses can be scheduled by publishing them in the future
+pub fn is_published(course: &courses::Model, now: DateTime<Utc>) -> bool {
+    course
+        .published_at
+        .is_some_and(|published_at| published_at <= now)
+}
+
+/// Filters a course query down to published courses
+pub fn published_condition() -> Condition {
+    Condition::all()
+        .add(courses::Column::PublishedAt.is_not_null())
+        .add(courses::Column::PublishedAt.lte(Utc::now()))
+}
+
+/// Finds a published course, or fails with `COURSE_NOT_FOUND`
+pub async fn find_published_course<C: ConnectionTrait>(
+    db: &C,
+    course_slug: &str,
+) -> async_graphql::Result<courses::Model> {
+    courses::Entity::find_by_id(course_slug.to_string())
+        .one(db)
+        .await?
+        .filter(|course| is_published(course, Utc::now()))
+        .ok_or_else(|| {
+            new_err(
+                "COURSE_NOT_FOUND",
+                &format!("There is no course {:?}", course_slug),
+            )
+        })
+}
+
+/// Finds a unit of a published course, or fails with `COURSE_NOT_FOUND` or
+/// `UNIT_NOT_FOUND`
+pub async fn find_unit<C: ConnectionTrait>(
+    db: &C,
+    course_slug: &str,
+    unit_slug: &str,
+) -> async_graphql::Result<course_units::Model> {
+    find_published_course(db, course_slug).await?;
+
+    course_units::Entity::find_by_id((course_slug.to_string(), unit_slug.to_string()))
+        .one(db)
+        .await?
+        .ok_or_else(|| {
+            new_err(
+                "UNIT_NOT_FOUND",
+                &format!(
+                    "There is no unit {:?} in the course {:?}",
+                    unit_slug, course_slug
+                ),
+            )
+        })
+}
+
+/// Finds a question of a published course, or fails with
+/// `COURSE_NOT_FOUND`, `UNIT_NOT_FOUND` or `QUESTION_NOT_FOUND`
+pub async fn find_question<C: ConnectionTrait>(
+    db: &C,
+    course_slug: &str,
+    unit_slug: &str,
+    question_slug: &str,
+) -> async_graphql::Result<unit_questions::Model> {
+    find_unit(db, course_slug, unit_slug).await?;
+
+    unit_questions::Entity::find_by_id((
+        course_slug.to_string(),
+        unit_slug.to_string(),
+        question_slug.to_string(),
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| {
+        new_err(
+            "QUESTION_NOT_FOUND",
+            &format!(
+                "There is no question {:?} in the unit {:?}",
+                question_slug, unit_slug
+            ),
+        )
+    })
+}
+
+/// Whether giving `slug` these prerequisites would make a course or unit
+/// its own prerequisite, directly or through others
+pub fn creates_prerequisite_cycle(
+    existing: &HashMap<String, Vec<String>>,
+    slug: &str,
+    prerequisites: &[String],
+) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = prerequisites.iter().collect::<Vec<_>>();
+
+    while let Some(prerequisite) = stack.pop() {
+        if prerequisite == slug {
+            return true;
+        }
+
+        if seen.insert(prerequisite) {
+            stack.extend(existing.get(prerequisite).into_iter().flatten());
+        }
+    }
+
+    false
+}
+
+/// Replaces a course's prerequisites, failing with `INVALID_PREREQUISITES`
+/// if they don't exist or would create a cycle
+pub async fn set_prerequisites<C: ConnectionTrait>(
+    db: &C,
+    course_slug: &str,
+    prerequisites: Vec<String>,
+) -> async_graphql::Result<()> {
+    let known = courses::Entity::find()
+        .filter(courses::Column::Slug.is_in(prerequisites.clone()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|course| course.slug)
+        .collect::<HashSet<_>>();
+
+    if let Some(unknown) = prerequisites.iter().find(|slug| !known.contains(*slug)) {
+        return Err(new_err(
+            "INVALID_PREREQUISITES",
+            &format!("There is no course {:?}", unknown),
+        ));
+    }
+
+    let mut existing: HashMap<String, Vec<String>> = HashMap::new();
+    for edge in course_prerequisites::Entity::find().all(db).await? {
+        existing
+            .entry(edge.course_slug)
+            .or_default()
+            .push(edge.prerequisite_slug);
+    }
+
+    if creates_prerequisite_cycle(&existing, course_slug, &prerequisites) {
+        return Err(new_err(
+            "INVALID_PREREQUISITES",
+            "A course can't be a prerequisite of itself",
+        ));
+    }
+
+    course_prerequisites::Entity::delete_many()
+        .filter(course_prerequisites::Column::CourseSlug.eq(course_slug))
+        .exec(db)
+        .await?;
+
+    if !prerequisites.is_empty() {
+        course_prerequisites::Entity::insert_many(prerequisites.into_iter().map(
+            |prerequisite_slug| course_prerequisites::ActiveModel {
+                course_slug: sea_orm::Set(course_slug.to_string()),
+                prerequisite_slug: sea_orm::Set(prerequisite_slug),
+            },
+        ))
+        .exec(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn prerequisites(edges: &[(&str, &str)]) -> HashMap<String, Vec<String>> {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+        for (course, prerequisite) in edges {
+            map.entry(course.to_string())
+                .or_default()
+                .push(prerequisite.to_string());
+        }
+        map
+    }
+
+    #[test]
+    fn validates_slugs() {
+        assert!(validate_slug("intro-to-lumina").is_ok());
+        assert!(validate_slug("unit-1").is_ok());
+        assert!(validate_slug("Intro to Lumina").is_err());
+        assert!(validate_slug("").is_err());
+    }
+
+    #[test]
+    fn scheduled_courses_are_not_published_yet() {
+        let now = Utc::now();
+        let mut course = courses::Model {
+            slug: "intro".to_string(),
+            title: "Intro".to_string(),
+            description: None,
+            position: 0,
+            published_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        assert!(!is_published(&course, now));
+
+        course.published_at = Some(now + Duration::days(1));
+        assert!(!is_published(&course, now));
+
+        course.published_at = Some(now - Duration::days(1));
+        assert!(is_published(&course, now));
+    }
+
+    #[test]
+    fn detects_prerequisite_cycles() {
+        let existing = prerequisites(&[("b", "a"), ("c", "b")]);
+
+        assert!(!creates_prerequisite_cycle(
+            &existing,
+            "d",
+            &["c".to_string()]
+        ));
+        assert!(creates_prerequisite_cycle(
+            &existing,
+            "a",
+            &["c".to_string()]
+        ));
+        assert!(creates_prerequisite_cycle(
+            &existing,
+            "a",
+            &["a".to_string()]
+        ));
+    }
+}
@@ -0,0 +1,546 @@
+use std::collections::{HashMap, HashSet};
+
+use async_graphql::{Enum, ErrorExtensions, InputObject, SimpleObject};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DeriveActiveEnum, EntityTrait, EnumIter, QueryFilter,
+    QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    courses::published_condition,
+    error::new_err,
+    schema::{
+        course_certificates, course_prerequisites, course_units, courses, question_assessments,
+        sea_orm_active_enums::{Assessment, UnitStatus},
+        unit_prerequisites, unit_progress, unit_questions,
+    },
+};
+
+/// What a learner has to do before they can complete a unit
+#[derive(
+    Enum, Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, EnumIter, DeriveActiveEnum,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+pub enum UnitPrerequisiteRule {
+    /// Every unit before this one in the course is completed
+    #[sea_orm(string_value = "PreviousUnitsCompleted")]
+    PreviousUnitsCompleted,
+    /// A specific unit of the course is completed
+    #[sea_orm(string_value = "UnitCompleted")]
+    UnitCompleted,
+    /// Every question in this unit is assessed as a pass or soft pass
+    #[sea_orm(string_value = "QuestionsPassed")]
+    QuestionsPassed,
+    /// A prerequisite of the unit's course is completed. These come from the
+    /// course's prerequisites and apply to every unit, so they can't be
+    /// given to a unit.
+    #[sea_orm(string_value = "CourseCompleted")]
+    CourseCompleted,
+}
+
+#[derive(InputObject, Debug, Clone, PartialEq, Eq)]
+#[graphql(rename_fields = "snake_case")]
+pub struct UnitPrerequisiteInput {
+    pub rule: UnitPrerequisiteRule,
+    /// Required for `UNIT_COMPLETED` rules
+    pub unit_slug: Option<String>,
+}
+
+/// Something a learner still has to do before completing a unit
+#[derive(SimpleObject, Serialize, Clone, Debug, PartialEq, Eq)]
+#[graphql(rename_fields = "snake_case")]
+pub struct MissingPrerequisite {
+    pub rule: UnitPrerequisiteRule,
+    /// The unit that has to be completed
+    pub unit_slug: Option<String>,
+    /// The question that has to be passed
+    pub question_slug: Option<String>,
+    /// The course that has to be completed
+    pub course_slug: Option<String>,
+    pub message: String,
+}
+
+impl MissingPrerequisite {
+    fn unit(rule: UnitPrerequisiteRule, unit_slug: &str) -> Self {
+        Self {
+            rule,
+            unit_slug: Some(unit_slug.to_string()),
+            question_slug: None,
+            course_slug: None,
+            message: format!("Complete the unit {:?} first", unit_slug),
+        }
+    }
+
+    fn question(question_slug: &str) -> Self {
+        Self {
+            rule: UnitPrerequisiteRule::QuestionsPassed,
+            unit_slug: None,
+            question_slug: Some(question_slug.to_string()),
+            course_slug: None,
+            message: format!("Pass the question {:?} first", question_slug),
+        }
+    }
+
+    fn course(course_slug: &str) -> Self {
+        Self {
+            rule: UnitPrerequisiteRule::CourseCompleted,
+            unit_slug: None,
+            question_slug: None,
+            course_slug: Some(course_slug.to_string()),
+            message: format!("Complete the course {:?} first", course_slug),
+        }
+    }
+}
+
+/// What the learner has done in a course so far
+#[derive(Debug, Default)]
+pub struct LearnerProgress {
+    pub completed_units: HashSet<String>,
+    /// Questions in the unit being completed that were passed or soft passed
+    pub passed_questions: HashSet<String>,
+}
+
+/// Every prerequisite of `unit` the learner hasn't met yet.
+///
+/// `units` are all units of the course and `questions` the questions of
+/// `unit`, both in course order.
+pub fn missing_prerequisites(
+    unit: &course_units::Model,
+    rules: &[unit_prerequisites::Model],
+    units: &[course_units::Model],
+    questions: &[unit_questions::Model],
+    progress: &LearnerProgress,
+) -> Vec<MissingPrerequisite> {
+    let mut missing: Vec<MissingPrerequisite> = Vec::new();
+    let mut push = |prerequisite: MissingPrerequisite| {
+        if !missing.contains(&prerequisite) {
+            missing.push(prerequisite);
+        }
+    };
+
+    for rule in rules {
+        match rule.rule {
+            UnitPrerequisiteRule::PreviousUnitsCompleted => earlier_units(unit, units)
+                .filter(|other| !progress.completed_units.contains(&other.slug))
+                .for_each(|other| push(MissingPrerequisite::unit(rule.rule, &other.slug))),
+            UnitPrerequisiteRule::UnitCompleted => {
+                if let Some(slug) = &rule.prerequisite_unit_slug {
+                    if !progress.completed_units.contains(slug) {
+                        push(MissingPrerequisite::unit(rule.rule, slug));
+                    }
+                }
+            }
+            UnitPrerequisiteRule::QuestionsPassed => questions
+                .iter()
+                .filter(|question| !progress.passed_questions.contains(&question.slug))
+                .for_each(|question| push(MissingPrerequisite::question(&question.slug))),
+            UnitPrerequisiteRule::CourseCompleted => {}
+        }
+    }
+
+    missing
+}
+
+/// The units before `unit` in course order
+fn earlier_units<'a>(
+    unit: &'a course_units::Model,
+    units: &'a [course_units::Model],
+) -> impl Iterator<Item = &'a course_units::Model> {
+    units
+        .iter()
+        .filter(move |other| (other.position, &other.slug) < (unit.position, &unit.slug))
+}
+
+/// The units that have to be completed before `unit` because of `rules`,
+/// with `PREVIOUS_UNITS_COMPLETED` requiring every earlier unit
+pub fn required_units<'a>(
+    unit: &course_units::Model,
+    rules: impl IntoIterator<Item = (UnitPrerequisiteRule, Option<&'a String>)>,
+    units: &[course_units::Model],
+) -> Vec<String> {
+    let mut required = Vec::new();
+
+    for (rule, prerequisite_unit_slug) in rules {
+        match rule {
+            UnitPrerequisiteRule::PreviousUnitsCompleted => {
+                required.extend(earlier_units(unit, units).map(|other| other.slug.clone()))
+            }
+            UnitPrerequisiteRule::UnitCompleted => required.extend(prerequisite_unit_slug.cloned()),
+            UnitPrerequisiteRule::QuestionsPassed | UnitPrerequisiteRule::CourseCompleted => {}
+        }
+    }
+
+    required
+}
+
+/// The units each unit of a course requires, for finding cycles with
+/// [`crate::courses::creates_prerequisite_cycle`]
+pub fn unit_prerequisite_graph(
+    rules: &[unit_prerequisites::Model],
+    units: &[course_units::Model],
+) -> HashMap<String, Vec<String>> {
+    units
+        .iter()
+        .map(|unit| {
+            let rules = rules
+                .iter()
+                .filter(|rule| rule.unit_slug == unit.slug)
+                .map(|rule| (rule.rule, rule.prerequisite_unit_slug.as_ref()));
+
+            (unit.slug.clone(), required_units(unit, rules, units))
+        })
+        .collect()
+}
+
+/// Checks the rules an admin is about to give a unit
+pub fn validate_prerequisites(
+    unit: &course_units::Model,
+    units: &[course_units::Model],
+    prerequisites: &[UnitPrerequisiteInput],
+) -> async_graphql::Result<()> {
+    for prerequisite in prerequisites {
+        match (prerequisite.rule, &prerequisite.unit_slug) {
+            (UnitPrerequisiteRule::UnitCompleted, Some(slug)) if slug == &unit.slug => {
+                return Err(new_err(
+                    "INVALID_PREREQUISITES",
+                    "A unit can't be a prerequisite of itself",
+                ))
+            }
+            (UnitPrerequisiteRule::UnitCompleted, Some(slug)) => {
+                if !units.iter().any(|other| &other.slug == slug) {
+                    return Err(new_err(
+                        "INVALID_PREREQUISITES",
+                        &format!(
+                            "There is no unit {:?} in the course {:?}",
+                            slug, unit.course_slug
+                        ),
+                    ));
+                }
+            }
+            (UnitPrerequisiteRule::CourseCompleted, _) => {
+                return Err(new_err(
+                    "INVALID_PREREQUISITES",
+                    "COURSE_COMPLETED prerequisites are set on the course, not its units",
+                ))
+            }
+            (UnitPrerequisiteRule::UnitCompleted, None) => {
+                return Err(new_err(
+                    "INVALID_PREREQUISITES",
+                    "UNIT_COMPLETED prerequisites need a unit_slug",
+                ))
+            }
+            (_, Some(_)) => {
+                return Err(new_err(
+                    "INVALID_PREREQUISITES",
+                    &format!(
+                        "Only UNIT_COMPLETED prerequisites take a unit_slug, not {:?}",
+                        prerequisite.rule
+                    ),
+                ))
+            }
+            (_, None) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The published prerequisites of the course the user hasn't completed,
+/// going by the certificates they have been issued
+async fn missing_courses<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    course_slug: &str,
+) -> Result<Vec<MissingPrerequisite>, sea_orm::DbErr> {
+    let required = course_prerequisites::Entity::find()
+        .filter(course_prerequisites::Column::CourseSlug.eq(course_slug))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|prerequisite| prerequisite.prerequisite_slug)
+        .collect::<Vec<_>>();
+
+    if required.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let completed = course_certificates::Entity::find()
+        .filter(course_certificates::Column::UserId.eq(user_id))
+        .filter(course_certificates::Column::CourseSlug.is_in(required.clone()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|certificate| certificate.course_slug)
+        .collect::<HashSet<_>>();
+
+    Ok(courses::Entity::find()
+        .filter(courses::Column::Slug.is_in(required))
+        .filter(published_condition())
+        .order_by_asc(courses::Column::Position)
+        .order_by_asc(courses::Column::Slug)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|course| !completed.contains(&course.slug))
+        .map(|course| MissingPrerequisite::course(&course.slug))
+        .collect())
+}
+
+/// Fails with `PREREQUISITES_NOT_MET`, listing what is missing in the
+/// `missing` extension, unless the user may complete the unit. The course's
+/// own prerequisites have to be completed before any of its units.
+pub async fn ensure_prerequisites_met<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    unit: &course_units::Model,
+) -> async_graphql::Result<()> {
+    let mut missing = missing_courses(db, user_id, &unit.course_slug).await?;
+
+    let rules = unit_prerequisites::Entity::find()
+        .filter(unit_prerequisites::Column::CourseSlug.eq(unit.course_slug.clone()))
+        .filter(unit_prerequisites::Column::UnitSlug.eq(unit.slug.clone()))
+        .all(db)
+        .await?;
+
+    if !rules.is_empty() {
+        missing.extend(unit_missing_prerequisites(db, user_id, unit, &rules).await?);
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let message = missing
+        .iter()
+        .map(|prerequisite| prerequisite.message.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let missing = async_graphql::to_value(&missing)?;
+
+    Err(new_err("PREREQUISITES_NOT_MET", &message)
+        .extend_with(|_, e| e.set("missing", missing.clone())))
+}
+
+/// Every prerequisite the unit's `rules` give it that the user hasn't met
+async fn unit_missing_prerequisites<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    unit: &course_units::Model,
+    rules: &[unit_prerequisites::Model],
+) -> Result<Vec<MissingPrerequisite>, sea_orm::DbErr> {
+    let units = course_units::Entity::find()
+        .filter(course_units::Column::CourseSlug.eq(unit.course_slug.clone()))
+        .order_by_asc(course_units::Column::Position)
+        .order_by_asc(course_units::Column::Slug)
+        .all(db)
+        .await?;
+
+    let questions = unit_questions::Entity::find()
+        .filter(unit_questions::Column::CourseSlug.eq(unit.course_slug.clone()))
+        .filter(unit_questions::Column::UnitSlug.eq(unit.slug.clone()))
+        .order_by_asc(unit_questions::Column::Position)
+        .order_by_asc(unit_questions::Column::Slug)
+        .all(db)
+        .await?;
+
+    let completed_units = unit_progress::Entity::find()
+        .filter(unit_progress::Column::UserId.eq(user_id))
+        .filter(unit_progress::Column::CourseSlug.eq(unit.course_slug.clone()))
+        .filter(unit_progress::Column::Status.eq(UnitStatus::Completed))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|progress| progress.unit_slug)
+        .collect();
+
+    let passed_questions = question_assessments::Entity::find()
+        .filter(question_assessments::Column::UserId.eq(user_id))
+        .filter(question_assessments::Column::CourseSlug.eq(unit.course_slug.clone()))
+        .filter(question_assessments::Column::UnitSlug.eq(unit.slug.clone()))
+        .filter(
+            Condition::any()
+                .add(question_assessments::Column::Assessment.eq(Assessment::Pass))
+                .add(question_assessments::Column::Assessment.eq(Assessment::SoftPass)),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|assessment| assessment.question_slug)
+        .collect();
+
+    Ok(missing_prerequisites(
+        unit,
+        rules,
+        &units,
+        &questions,
+        &LearnerProgress {
+            completed_units,
+            passed_questions,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(slug: &str, position: i32) -> course_units::Model {
+        course_units::Model {
+            course_slug: "course".to_string(),
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            description: None,
+            position,
+        }
+    }
+
+    fn rule(rule: UnitPrerequisiteRule, unit_slug: Option<&str>) -> unit_prerequisites::Model {
+        unit_prerequisites::Model {
+            id: Uuid::new_v4(),
+            course_slug: "course".to_string(),
+            unit_slug: "third".to_string(),
+            rule,
+            prerequisite_unit_slug: unit_slug.map(str::to_string),
+        }
+    }
+
+    fn question(slug: &str) -> unit_questions::Model {
+        unit_questions::Model {
+            course_slug: "course".to_string(),
+            unit_slug: "third".to_string(),
+            slug: slug.to_string(),
+            question: "Why?".to_string(),
+            context: None,
+            reference_answer: None,
+            rubric: None,
+            position: 0,
+        }
+    }
+
+    fn units() -> Vec<course_units::Model> {
+        vec![unit("first", 0), unit("second", 1), unit("third", 2)]
+    }
+
+    fn progress(completed_units: &[&str], passed_questions: &[&str]) -> LearnerProgress {
+        LearnerProgress {
+            completed_units: completed_units.iter().map(|s| s.to_string()).collect(),
+            passed_questions: passed_questions.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn previous_units_must_be_completed() {
+        let units = units();
+        let rules = [rule(UnitPrerequisiteRule::PreviousUnitsCompleted, None)];
+
+        let missing =
+            missing_prerequisites(&units[2], &rules, &units, &[], &progress(&["first"], &[]));
+
+        assert_eq!(
+            missing,
+            [MissingPrerequisite::unit(
+                UnitPrerequisiteRule::PreviousUnitsCompleted,
+                "second"
+            )]
+        );
+        assert!(missing_prerequisites(
+            &units[2],
+            &rules,
+            &units,
+            &[],
+            &progress(&["first", "second"], &[])
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn questions_must_be_passed() {
+        let units = units();
+        let rules = [rule(UnitPrerequisiteRule::QuestionsPassed, None)];
+        let questions = [question("why"), question("how")];
+
+        let missing = missing_prerequisites(
+            &units[2],
+            &rules,
+            &units,
+            &questions,
+            &progress(&[], &["why"]),
+        );
+
+        assert_eq!(missing, [MissingPrerequisite::question("how")]);
+    }
+
+    #[test]
+    fn specific_units_must_be_completed() {
+        let units = units();
+        let rules = [rule(UnitPrerequisiteRule::UnitCompleted, Some("first"))];
+
+        assert_eq!(
+            missing_prerequisites(&units[2], &rules, &units, &[], &progress(&["second"], &[])),
+            [MissingPrerequisite::unit(
+                UnitPrerequisiteRule::UnitCompleted,
+                "first"
+            )]
+        );
+    }
+
+    #[test]
+    fn previous_units_are_part_of_the_prerequisite_graph() {
+        let units = units();
+        let mut second = rule(UnitPrerequisiteRule::PreviousUnitsCompleted, None);
+        second.unit_slug = "second".to_string();
+
+        let graph = unit_prerequisite_graph(&[second], &units);
+
+        assert_eq!(graph["second"], ["first"]);
+        assert!(graph["first"].is_empty());
+
+        // the first unit can't require the third, which already requires it
+        // through the second
+        let first_requires = required_units(
+            &units[0],
+            [(
+                UnitPrerequisiteRule::UnitCompleted,
+                Some(&"third".to_string()),
+            )],
+            &units,
+        );
+        let mut third = rule(UnitPrerequisiteRule::PreviousUnitsCompleted, None);
+        third.unit_slug = "third".to_string();
+        let graph = unit_prerequisite_graph(&[third], &units);
+
+        assert!(crate::courses::creates_prerequisite_cycle(
+            &graph,
+            "first",
+            &first_requires
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let units = units();
+        let input = |rule, unit_slug: Option<&str>| UnitPrerequisiteInput {
+            rule,
+            unit_slug: unit_slug.map(str::to_string),
+        };
+
+        assert!(validate_prerequisites(
+            &units[2],
+            &units,
+            &[input(UnitPrerequisiteRule::UnitCompleted, Some("first"))]
+        )
+        .is_ok());
+
+        for invalid in [
+            input(UnitPrerequisiteRule::UnitCompleted, Some("third")),
+            input(UnitPrerequisiteRule::UnitCompleted, Some("missing")),
+            input(UnitPrerequisiteRule::UnitCompleted, None),
+            input(UnitPrerequisiteRule::QuestionsPassed, Some("first")),
+        ] {
+            assert!(validate_prerequisites(&units[2], &units, &[invalid]).is_err());
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm::{ConnectionTrait, DbBackend, FromQueryResult, Statement};
+use uuid::Uuid;
+
+use crate::graphql::types::course::CourseProgressSummary;
+
+/// Summarises a user's progress through published courses.
+///
+/// With a `course_slug`, returns that course's summary even if the user
+/// hasn't started it. Otherwise returns every course the user has started,
+/// most recently active first. Progress on units that aren't in the
+/// catalog is ignored.
+pub async fn course_progress_summaries<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    course_slug: Option<&str>,
+) -> Result<Vec<CourseProgressSummary>, sea_orm::DbErr> {
+    CourseProgressSummary::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT
+            c.slug AS course_slug,
+            count(u.slug) AS total_units,
+            count(u.slug) FILTER (WHERE p.status = 'Completed') AS completed_units,
+            count(u.slug) FILTER (WHERE p.status = 'InProgress') AS in_progress_units,
+            CASE
+                WHEN count(u.slug) = 0 THEN 0
+                ELSE (100 * count(u.slug) FILTER (WHERE p.status = 'Completed') / count(u.slug))::integer
+            END AS percent_complete,
+            max(p.updated_at) AS last_activity_at,
+            (
+                SELECT next.slug
+                FROM course_units next
+                LEFT JOIN unit_progress next_progress
+                    ON next_progress.course_slug = next.course_slug
+                    AND next_progress.unit_slug = next.slug
+                    AND next_progress.user_id = $1
+                WHERE next.course_slug = c.slug
+                    AND next_progress.status IS DISTINCT FROM 'Completed'
+                ORDER BY next.position, next.slug
+                LIMIT 1
+            ) AS next_unit_slug
+        FROM courses c
+        LEFT JOIN course_units u ON u.course_slug = c.slug
+        LEFT JOIN unit_progress p
+            ON p.course_slug = u.course_slug
+            AND p.unit_slug = u.slug
+            AND p.user_id = $1
+        WHERE c.published_at <= now()
+            AND ($2::varchar IS NULL OR c.slug = $2)
+        GROUP BY c.slug
+        HAVING $2::varchar IS NOT NULL OR max(p.updated_at) IS NOT NULL
+        ORDER BY last_activity_at DESC NULLS LAST, c.slug
+        "#,
+        [user_id.into(), course_slug.map(str::to_string).into()],
+    ))
+    .all(db)
+    .await
+}
@@ -0,0 +1,224 @@
+//! Conversations with the AI tutor about an assessment. Each attempt at a
+//! question gets its own thread, since the tutor's replies are about that
+//! attempt's answer and feedback.
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    ai_usage::{reserve_request, settle_usage, TUTOR_FEATURE},
+    assessments::{
+        injection::looks_like_prompt_injection,
+        tutor::{TutorRequest, TutorRole},
+        AssessmentProvider,
+    },
+    courses::find_question,
+    error::new_err,
+    graphql::types::user::User,
+    schema::{question_assessments, tutor_messages, tutor_threads},
+};
+
+/// Longer messages would only make replies slower and more expensive
+pub const MAX_MESSAGE_LENGTH: usize = 1_000;
+/// How many messages a learner can send in each thread
+pub const MAX_THREAD_MESSAGES: u64 = 10;
+
+const INJECTION_MESSAGE: &str = "Your message looks like it contains instructions for the \
+                                 tutor, so it wasn't sent. Ask about your answer instead.";
+
+/// Finds the learner's thread about their current attempt, creating it if
+/// they haven't started one
+async fn thread_for<C: ConnectionTrait>(
+    db: &C,
+    assessment: &question_assessments::Model,
+    user_id: Uuid,
+) -> Result<tutor_threads::Model, sea_orm::DbErr> {
+    tutor_threads::Entity::insert(
+        tutor_threads::Model {
+            id: Uuid::new_v4(),
+            question_assessment_id: assessment.id,
+            attempt: assessment.attempt,
+            user_id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+        .into_active_model(),
+    )
+    .on_conflict(
+        OnConflict::columns([
+            tutor_threads::Column::QuestionAssessmentId,
+            tutor_threads::Column::Attempt,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    tutor_threads::Entity::find()
+        .filter(tutor_threads::Column::QuestionAssessmentId.eq(assessment.id))
+        .filter(tutor_threads::Column::Attempt.eq(assessment.attempt))
+        .one(db)
+        .await?
+        .ok_or_else(|| sea_orm::DbErr::RecordNotFound("tutor thread".to_string()))
+}
+
+/// How many more messages the learner can send in the thread
+pub async fn remaining_messages<C: ConnectionTrait>(
+    db: &C,
+    thread_id: Uuid,
+) -> Result<u64, sea_orm::DbErr> {
+    let sent = tutor_messages::Entity::find()
+        .filter(tutor_messages::Column::ThreadId.eq(thread_id))
+        .filter(tutor_messages::Column::Role.eq(TutorRole::Learner))
+        .count(db)
+        .await?;
+
+    Ok(MAX_THREAD_MESSAGES.saturating_sub(sent))
+}
+
+/// Asks the tutor to reply to the thread, whose last message is the
+/// learner's, counting the request towards the learner's quota
+async fn tutor_reply(
+    db: &DatabaseConnection,
+    provider: &dyn AssessmentProvider,
+    user: &User,
+    assessment: &question_assessments::Model,
+    thread_id: Uuid,
+) -> async_graphql::Result<String> {
+    let usage_id = reserve_request(db, user, TUTOR_FEATURE, provider.model()).await?;
+
+    let question = find_question(
+        db,
+        &assessment.course_slug,
+        &assessment.unit_slug,
+        &assessment.question_slug,
+    )
+    .await?;
+
+    let messages = tutor_messages::Entity::find()
+        .filter(tutor_messages::Column::ThreadId.eq(thread_id))
+        .order_by_asc(tutor_messages::Column::CreatedAt)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|message| (message.role, message.content))
+        .collect::<Vec<_>>();
+
+    let reply = provider
+        .tutor(&TutorRequest {
+            question: question.question,
+            question_context: question.context,
+            answer: assessment.answer.clone(),
+            assessment: assessment.assessment,
+            feedback: assessment.feedback.clone(),
+            messages,
+            user: slug::slugify(&user.first_name),
+        })
+        .await?;
+    settle_usage(db, usage_id, &reply.usage).await?;
+
+    Ok(reply.content)
+}
+
+/// Sends the learner's message to the tutor about their latest attempt at
+/// a question, and keeps both it and the tutor's reply. Messages that look
+/// like instructions for the tutor are rejected without being sent.
+pub async fn send_tutor_message(
+    db: &DatabaseConnection,
+    provider: &dyn AssessmentProvider,
+    user: &User,
+    question_assessment_id: Uuid,
+    content: String,
+) -> async_graphql::Result<tutor_threads::Model> {
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return Err(new_err("EMPTY_MESSAGE", "Messages can't be empty"));
+    }
+    if content.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(new_err(
+            "MESSAGE_TOO_LONG",
+            &format!(
+                "Messages can be at most {} characters long",
+                MAX_MESSAGE_LENGTH
+            ),
+        ));
+    }
+
+    let assessment = question_assessments::Entity::find_by_id(question_assessment_id)
+        .filter(question_assessments::Column::UserId.eq(user.id))
+        .one(db)
+        .await?
+        .ok_or_else(|| new_err("ASSESSMENT_NOT_FOUND", "You haven't answered this question"))?;
+
+    if looks_like_prompt_injection(&content) {
+        return Err(new_err("MESSAGE_REJECTED", INJECTION_MESSAGE));
+    }
+
+    let thread = thread_for(db, &assessment, user.id).await?;
+
+    // the learner's message is saved straight away to take its place in the
+    // thread, with the thread locked so messages sent at the same time can't
+    // both take the last one left
+    let txn = db.begin().await?;
+    let thread = tutor_threads::Entity::find_by_id(thread.id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| sea_orm::DbErr::RecordNotFound("tutor thread".to_string()))?;
+    if remaining_messages(&txn, thread.id).await? == 0 {
+        return Err(new_err(
+            "THREAD_LIMIT_REACHED",
+            &format!(
+                "You can send at most {} messages about each answer, try answering again",
+                MAX_THREAD_MESSAGES
+            ),
+        ));
+    }
+    let message = tutor_messages::Model {
+        id: Uuid::new_v4(),
+        thread_id: thread.id,
+        role: TutorRole::Learner,
+        content,
+        created_at: Utc::now(),
+    }
+    .into_active_model()
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+
+    // no transaction is open while the model replies, which takes seconds
+    let reply = match tutor_reply(db, provider, user, &assessment, thread.id).await {
+        Ok(reply) => reply,
+        Err(err) => {
+            tutor_messages::Entity::delete_by_id(message.id)
+                .exec(db)
+                .await?;
+            return Err(err);
+        }
+    };
+
+    let txn = db.begin().await?;
+    tutor_messages::Model {
+        id: Uuid::new_v4(),
+        thread_id: thread.id,
+        role: TutorRole::Tutor,
+        content: reply,
+        created_at: Utc::now(),
+    }
+    .into_active_model()
+    .insert(&txn)
+    .await?;
+
+    let mut active_model = thread.into_active_model();
+    active_model.updated_at = Set(Utc::now());
+    let thread = active_model.update(&txn).await?;
+    txn.commit().await?;
+
+    Ok(thread)
+}
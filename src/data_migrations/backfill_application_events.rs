@@ -0,0 +1,33 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// Builds a timeline for applications submitted before application events
+/// were recorded, from the application itself and its reviews.
+///
+/// Applications that already have any events are left alone.
+pub async fn run(db: &DatabaseConnection) -> Result<(), anyhow::Error> {
+    let created = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."application_events" ("id", "application_id", "kind", "actor_id", "status", "message", "internal", "created_at")
+            SELECT gen_random_uuid(), "application_id", "kind", "actor_id", "status", "message", false, "created_at"
+            FROM (
+                SELECT "id" AS "application_id", 'Submitted' AS "kind", "user_id" AS "actor_id",
+                    NULL AS "status", NULL AS "message", "created_at"
+                FROM "public"."applications"
+                UNION ALL
+                SELECT "application_id", 'StatusChanged', "reviewer_id", "status", "reason", "created_at"
+                FROM "public"."application_reviews"
+            ) AS "history"
+            WHERE NOT EXISTS (
+                SELECT 1 FROM "public"."application_events"
+                WHERE "application_events"."application_id" = "history"."application_id"
+            )
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    tracing::info!("Created {} application events", created);
+
+    Ok(())
+}
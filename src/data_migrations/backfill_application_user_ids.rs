@@ -0,0 +1,111 @@
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, FromQueryResult, Statement, TransactionTrait,
+};
+use uuid::Uuid;
+
+/// Matches the text form of a UUID, so malformed ids aren't cast
+const UUID_PATTERN: &str =
+    "^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$";
+
+/// An application that can't be given a user
+#[derive(Debug, FromQueryResult)]
+struct Unmatched {
+    id: Uuid,
+    reason: String,
+}
+
+/// Moves `application->>'user_id'` into the `applications.user_id` column.
+///
+/// This has to run before `./migrate.sh` applies the `NOT NULL` column from
+/// `schema.sql`, so it adds the column itself. Applications that can't be
+/// matched to an existing user are never deleted: they are listed, left in
+/// place, and the column stays nullable until they have been fixed by hand.
+///
+/// With `dry_run`, everything is rolled back after logging what would
+/// change.
+pub async fn run(db: &DatabaseConnection, dry_run: bool) -> Result<(), anyhow::Error> {
+    let txn = db.begin().await?;
+
+    txn.execute_unprepared(
+        r#"ALTER TABLE "public"."applications" ADD COLUMN IF NOT EXISTS "user_id" uuid"#,
+    )
+    .await?;
+
+    let backfilled = txn
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            UPDATE "public"."applications"
+            SET "user_id" = ("application"->>'user_id')::uuid
+            WHERE "user_id" IS NULL AND "application"->>'user_id' ~ $1
+            "#,
+            [UUID_PATTERN.into()],
+        ))
+        .await?
+        .rows_affected();
+
+    let unmatched = Unmatched::find_by_statement(Statement::from_string(
+        DbBackend::Postgres,
+        r#"
+        SELECT "id", CASE
+            WHEN "user_id" IS NOT NULL THEN 'the user no longer exists'
+            WHEN "application" ? 'user_id' THEN 'the user_id is malformed'
+            ELSE 'there is no user_id'
+        END AS "reason"
+        FROM "public"."applications"
+        WHERE "user_id" IS NULL
+            OR "user_id" NOT IN (SELECT "id" FROM "public"."users")
+        ORDER BY "id"
+        "#
+        .to_string(),
+    ))
+    .all(&txn)
+    .await?;
+
+    // the id stays in the JSON of applications without a user, so it can
+    // still be fixed
+    txn.execute_unprepared(
+        r#"
+        UPDATE "public"."applications" SET "application" = "application" - 'user_id'
+        WHERE "application" ? 'user_id' AND "user_id" IS NOT NULL
+        "#,
+    )
+    .await?;
+
+    for application in &unmatched {
+        tracing::warn!(
+            "Application {} can't be given a user: {}",
+            application.id,
+            application.reason
+        );
+    }
+
+    if unmatched.is_empty() {
+        txn.execute_unprepared(
+            r#"ALTER TABLE "public"."applications" ALTER COLUMN "user_id" SET NOT NULL"#,
+        )
+        .await?;
+    }
+
+    tracing::info!(
+        "Backfilled user_id for {} applications, {} applications have no user",
+        backfilled,
+        unmatched.len()
+    );
+
+    if dry_run {
+        txn.rollback().await?;
+        tracing::info!("Dry run, rolled back");
+        return Ok(());
+    }
+    txn.commit().await?;
+
+    if !unmatched.is_empty() {
+        anyhow::bail!(
+            "{} applications have no user and were left in place. Fix or remove them by hand, then run this again before ./migrate.sh",
+            unmatched.len()
+        );
+    }
+
+    Ok(())
+}
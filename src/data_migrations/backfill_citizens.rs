@@ -0,0 +1,31 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// Creates citizen records for users whose citizenship application was
+/// approved before citizens were stored separately.
+///
+/// Numbers are handed out in the order the approved applications were
+/// submitted, and users who already have a record are left alone.
+pub async fn run(db: &DatabaseConnection) -> Result<(), anyhow::Error> {
+    let created = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."citizens" ("user_id", "application_id", "status", "granted_at", "updated_at")
+            SELECT "user_id", "id", 'Active', "created_at", now()
+            FROM (
+                SELECT DISTINCT ON ("user_id") "user_id", "id", "created_at"
+                FROM "public"."applications"
+                WHERE "application_type" = 'citizenship'
+                    AND "application"->>'citizenship_status' = 'Approved'
+                ORDER BY "user_id", "created_at"
+            ) AS "approved"
+            ORDER BY "created_at"
+            ON CONFLICT ("user_id") DO NOTHING
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    tracing::info!("Created {} citizen records", created);
+
+    Ok(())
+}
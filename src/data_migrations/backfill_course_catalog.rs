@@ -0,0 +1,63 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// Adds every course, unit and question that learners already have progress
+/// or assessments for to the course catalog, so they can keep using them
+/// once unknown slugs are rejected.
+///
+/// Courses are published with their slugs as placeholder titles and
+/// questions, which admins should replace. Existing catalog entries are
+/// left alone.
+pub async fn run(db: &DatabaseConnection) -> Result<(), anyhow::Error> {
+    let courses = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."courses" ("slug", "title", "published_at")
+            SELECT DISTINCT "course_slug", "course_slug", now()
+            FROM (
+                SELECT "course_slug" FROM "public"."unit_progress"
+                UNION
+                SELECT "course_slug" FROM "public"."question_assessments"
+            ) AS "used"
+            ON CONFLICT ("slug") DO NOTHING
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    let units = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."course_units" ("course_slug", "slug", "title")
+            SELECT DISTINCT "course_slug", "unit_slug", "unit_slug"
+            FROM (
+                SELECT "course_slug", "unit_slug" FROM "public"."unit_progress"
+                UNION
+                SELECT "course_slug", "unit_slug" FROM "public"."question_assessments"
+            ) AS "used"
+            ON CONFLICT ("course_slug", "slug") DO NOTHING
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    let questions = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."unit_questions" ("course_slug", "unit_slug", "slug", "question")
+            SELECT DISTINCT "course_slug", "unit_slug", "question_slug", "question_slug"
+            FROM "public"."question_assessments"
+            ON CONFLICT ("course_slug", "unit_slug", "slug") DO NOTHING
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    tracing::info!(
+        "Created {} courses, {} units and {} questions",
+        courses,
+        units,
+        questions
+    );
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// Records each assessment made before attempts were kept as the learner's
+/// first attempt at the question.
+///
+/// Questions that already have attempts, and assessments of questions that
+/// aren't in the course catalog, are left alone.
+pub async fn run(db: &DatabaseConnection) -> Result<(), anyhow::Error> {
+    let created = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."question_attempts" ("id", "user_id", "course_slug", "unit_slug", "question_slug", "attempt", "answer", "assessment", "feedback", "created_at")
+            SELECT gen_random_uuid(), "qa"."user_id", "qa"."course_slug", "qa"."unit_slug", "qa"."question_slug",
+                1, "qa"."answer", "qa"."assessment", "qa"."feedback", "qa"."updated_at"
+            FROM "public"."question_assessments" AS "qa"
+            JOIN "public"."unit_questions" AS "q"
+                ON "q"."course_slug" = "qa"."course_slug"
+                AND "q"."unit_slug" = "qa"."unit_slug"
+                AND "q"."slug" = "qa"."question_slug"
+            WHERE "qa"."user_id" IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM "public"."question_attempts" AS "a"
+                    WHERE "a"."user_id" = "qa"."user_id"
+                        AND "a"."course_slug" = "qa"."course_slug"
+                        AND "a"."unit_slug" = "qa"."unit_slug"
+                        AND "a"."question_slug" = "qa"."question_slug"
+                )
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    tracing::info!("Created {} question attempts", created);
+
+    Ok(())
+}
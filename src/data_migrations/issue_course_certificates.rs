@@ -0,0 +1,37 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// Issues certificates to learners who completed every unit of a course
+/// before certificates existed. They are dated when the learner's last unit
+/// was completed.
+///
+/// Learners who already have a certificate for a course are left alone.
+pub async fn run(db: &DatabaseConnection) -> Result<(), anyhow::Error> {
+    let issued = db
+        .execute_unprepared(
+            r#"
+            INSERT INTO "public"."course_certificates" ("id", "user_id", "course_slug", "learner_name", "course_title", "completed_at", "issued_at")
+            SELECT gen_random_uuid(), "users"."id", "courses"."slug",
+                "users"."first_name" || ' ' || "users"."last_name", "courses"."title",
+                max("unit_progress"."updated_at"), now()
+            FROM "public"."courses"
+            JOIN "public"."course_units" ON "course_units"."course_slug" = "courses"."slug"
+            JOIN "public"."unit_progress"
+                ON "unit_progress"."course_slug" = "course_units"."course_slug"
+                AND "unit_progress"."unit_slug" = "course_units"."slug"
+                AND "unit_progress"."status" = 'Completed'
+            JOIN "public"."users" ON "users"."id" = "unit_progress"."user_id"
+            GROUP BY "users"."id", "courses"."slug"
+            HAVING count(*) = (
+                SELECT count(*) FROM "public"."course_units"
+                WHERE "course_units"."course_slug" = "courses"."slug"
+            )
+            ON CONFLICT ("user_id", "course_slug") DO NOTHING
+            "#,
+        )
+        .await?
+        .rows_affected();
+
+    tracing::info!("Issued {} course certificates", issued);
+
+    Ok(())
+}
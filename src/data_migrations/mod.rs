@@ -0,0 +1,48 @@
+//! One-off data migrations
+//!
+//! Schema changes are applied from `schema.sql` by atlas, which can't
+//! rewrite existing rows. Data fixes live here instead, and are run with
+//! `cargo run --bin data_migration -- <name>`. Every migration must be
+//! safe to run more than once. Migrations that support it preview their
+//! changes with `--dry-run`.
+
+mod backfill_application_events;
+mod backfill_application_user_ids;
+mod backfill_citizens;
+mod backfill_course_catalog;
+mod backfill_question_attempts;
+mod issue_course_certificates;
+mod normalise_phone_numbers;
+
+use sea_orm::DatabaseConnection;
+
+pub const MIGRATIONS: &[&str] = &[
+    "normalise_phone_numbers",
+    "backfill_application_user_ids",
+    "backfill_citizens",
+    "backfill_application_events",
+    "backfill_course_catalog",
+    "issue_course_certificates",
+    "backfill_question_attempts",
+];
+
+pub async fn run(db: &DatabaseConnection, name: &str, dry_run: bool) -> Result<(), anyhow::Error> {
+    if dry_run && name != "backfill_application_user_ids" {
+        anyhow::bail!("{} doesn't support --dry-run", name);
+    }
+
+    match name {
+        "normalise_phone_numbers" => normalise_phone_numbers::run(db).await,
+        "backfill_application_user_ids" => backfill_application_user_ids::run(db, dry_run).await,
+        "backfill_citizens" => backfill_citizens::run(db).await,
+        "backfill_application_events" => backfill_application_events::run(db).await,
+        "backfill_course_catalog" => backfill_course_catalog::run(db).await,
+        "issue_course_certificates" => issue_course_certificates::run(db).await,
+        "backfill_question_attempts" => backfill_question_attempts::run(db).await,
+        _ => Err(anyhow::anyhow!(
+            "Unknown data migration: {}, expected one of {:?}",
+            name,
+            MIGRATIONS
+        )),
+    }
+}
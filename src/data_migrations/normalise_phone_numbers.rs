@@ -0,0 +1,69 @@
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set, Unchanged};
+
+use crate::{schema::users, util::phone::normalise_phone_number};
+
+/// Rewrites every user's phone number in E.164 format.
+///
+/// Older clients sent the calling code and country code the wrong way
+/// round, so if a row doesn't validate as-is the swapped order is tried
+/// too. Rows that still don't validate are logged and left untouched.
+pub async fn run(db: &DatabaseConnection) -> Result<(), anyhow::Error> {
+    let users = users::Entity::find().all(db).await?;
+
+    let mut updated = 0;
+    let mut invalid = 0;
+
+    for user in &users {
+        let phone = match normalise_phone_number(
+            &user.calling_code,
+            &user.country_code,
+            &user.phone_number,
+        ) {
+            Ok(phone) => phone,
+            Err(_) => match normalise_phone_number(
+                &user.country_code,
+                &user.calling_code,
+                &user.phone_number,
+            ) {
+                Ok(phone) => phone,
+                Err(e) => {
+                    tracing::warn!(
+                        "Could not normalise phone number of {}: {}",
+                        user.id,
+                        e.message
+                    );
+                    invalid += 1;
+                    continue;
+                }
+            },
+        };
+
+        if phone.calling_code == user.calling_code
+            && phone.country_code == user.country_code
+            && phone.e164 == user.phone_number
+        {
+            continue;
+        }
+
+        users::ActiveModel {
+            id: Unchanged(user.id),
+            calling_code: Set(phone.calling_code),
+            country_code: Set(phone.country_code),
+            phone_number: Set(phone.e164),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+
+        updated += 1;
+    }
+
+    tracing::info!(
+        "Normalised {} of {} phone numbers, {} could not be normalised",
+        updated,
+        users.len(),
+        invalid
+    );
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub mod mutations;
 pub mod queries;
+pub mod subscriptions;
 pub mod types;
@@ -0,0 +1,66 @@
+use async_graphql::{Context, Object};
+use chrono::Utc;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait, IntoActiveModel};
+use uuid::Uuid;
+
+use crate::{
+    error::new_err,
+    guards::role::{RoleGuard, ADMIN_ROLE},
+    schema::{ai_quotas, users},
+};
+
+#[derive(Default)]
+pub struct AiUsageMutation;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl AiUsageMutation {
+    /// Gives the user their own number of AI requests a day, instead of the
+    /// quota for their subscription. Passing no quota removes it.
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn set_ai_quota(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        daily_requests: Option<i32>,
+    ) -> async_graphql::Result<Option<i32>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        users::Entity::find_by_id(user_id)
+            .one(conn)
+            .await?
+            .ok_or_else(|| new_err("USER_NOT_FOUND", "User not found"))?;
+
+        let Some(daily_requests) = daily_requests else {
+            ai_quotas::Entity::delete_by_id(user_id).exec(conn).await?;
+            return Ok(None);
+        };
+
+        if daily_requests < 0 {
+            return Err(new_err(
+                "INVALID_QUOTA",
+                "The daily quota can't be negative",
+            ));
+        }
+
+        ai_quotas::Entity::insert(
+            ai_quotas::Model {
+                user_id,
+                daily_requests,
+                updated_at: Utc::now(),
+            }
+            .into_active_model(),
+        )
+        .on_conflict(
+            OnConflict::column(ai_quotas::Column::UserId)
+                .update_columns([
+                    ai_quotas::Column::DailyRequests,
+                    ai_quotas::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(conn)
+        .await?;
+
+        Ok(Some(daily_requests))
+    }
+}
@@ -1,36 +1,76 @@
-use crate::applications::{CitizenshipApplication, CitizenshipStatus};
+use crate::applications::{
+    eligibility::open_application_conflict,
+    events::{record_event, NewApplicationEvent},
+    validate_application, ApplicationInput, ApplicationKind, CitizenshipApplicationInput,
+};
 use crate::error::new_err;
 use crate::graphql::types::user::User;
 use crate::guards::auth::AuthGuard;
-use crate::{
-    applications::validate_application, graphql::types::application::Application,
-    schema::applications,
-};
+use crate::schema::applications;
 use async_graphql::{Context, Object};
 use chrono::{DateTime, Utc};
-use sea_orm::{DatabaseConnection, EntityTrait, IntoActiveModel};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, EntityTrait, IntoActiveModel, TransactionTrait,
+};
 
 #[derive(Default)]
 pub struct ApplicationMutation;
 
+pub(super) async fn insert_application(
+    db: &DatabaseConnection,
+    user: &User,
+    application: ApplicationKind,
+) -> async_graphql::Result<uuid::Uuid> {
+    validate_application(db, user, &application).await?;
+
+    let txn = db.begin().await?;
+    let id = insert_validated_application(&txn, user, application).await?;
+    txn.commit().await?;
+
+    Ok(id)
+}
+
+/// Inserts an application that has already been validated and records its
+/// submission, so callers can do both in their own transaction
+pub(super) async fn insert_validated_application<C: ConnectionTrait>(
+    db: &C,
+    user: &User,
+    application: ApplicationKind,
+) -> async_graphql::Result<uuid::Uuid> {
+    let form_version = application.form_version();
+    let (application_type, application) = application.into_columns()?;
+
+    let model = applications::Model {
+        id: uuid::Uuid::new_v4(),
+        user_id: user.id,
+        created_at: Utc::now(),
+        application,
+        application_type,
+        form_version,
+    };
+
+    let id = applications::Entity::insert(model.into_active_model())
+        .exec_with_returning(db)
+        .await
+        .map_err(open_application_conflict)?
+        .id;
+    record_event(db, id, NewApplicationEvent::submitted(user.id)).await?;
+
+    Ok(id)
+}
+
 #[Object(rename_fields = "snake_case", rename_args = "snake_case")]
 impl ApplicationMutation {
     #[graphql(guard = "AuthGuard")]
     pub async fn submit_application(
         &self,
         ctx: &Context<'_>,
-        application: Application,
+        application: ApplicationInput,
     ) -> async_graphql::Result<uuid::Uuid> {
         let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
 
-        validate_application(&application).await?;
-
-        Ok(
-            applications::Entity::insert(application.into_active_model())
-                .exec_with_returning(db)
-                .await?
-                .id,
-        )
+        insert_application(db, user, application.into_kind(db).await?).await
     }
 
     #[graphql(guard = "AuthGuard")]
@@ -52,9 +92,7 @@ impl ApplicationMutation {
         let db = ctx.data_unchecked::<DatabaseConnection>();
         let user = ctx.data_unchecked::<User>();
 
-        let citizenship_application = CitizenshipApplication {
-            user_id: user.id,
-            citizenship_status: CitizenshipStatus::Pending,
+        let application = ApplicationInput::Citizenship(CitizenshipApplicationInput {
             country_of_birth,
             country_of_citizenship,
             country_of_residence,
@@ -69,20 +107,8 @@ impl ApplicationMutation {
                     .ok_or_else(|| new_err("INVALID_DATE", "Invalid date of birth provided"))?,
                 Utc,
             ),
-        };
-
-        let application = Application {
-            application: serde_json::to_value(citizenship_application)?,
-            application_type: "citizenship".to_string(),
-            created_at: chrono::Utc::now(),
-            id: uuid::Uuid::new_v4(),
-        };
+        });
 
-        Ok(
-            applications::Entity::insert(application.into_active_model())
-                .exec_with_returning(db)
-                .await?
-                .id,
-        )
+        insert_application(db, user, application.into_kind(db).await?).await
     }
 }
@@ -0,0 +1,89 @@
+use std::{io::Read, sync::Arc};
+
+use async_graphql::{Context, Object, Upload};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel};
+use tracing::{event, Level};
+use uuid::Uuid;
+
+use crate::{
+    applications::documents::validate_document,
+    error::new_err,
+    graphql::types::user::User,
+    guards::auth::AuthGuard,
+    schema::{application_documents, applications},
+    storage::DocumentStorage,
+};
+
+#[derive(Default)]
+pub struct ApplicationDocumentMutation;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl ApplicationDocumentMutation {
+    /// Attach a document, such as a passport scan, to one of your applications.
+    /// Must be sent as a GraphQL multipart request.
+    #[graphql(guard = "AuthGuard")]
+    pub async fn upload_application_document(
+        &self,
+        ctx: &Context<'_>,
+        application_id: Uuid,
+        file: Upload,
+    ) -> async_graphql::Result<application_documents::Model> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+        let storage = ctx.data_unchecked::<Arc<dyn DocumentStorage>>();
+
+        match applications::Entity::find_by_id(application_id)
+            .one(db)
+            .await?
+        {
+            Some(application) if application.user_id == user.id => {}
+            _ => {
+                return Err(new_err(
+                    "APPLICATION_NOT_FOUND",
+                    "No application found with that id",
+                ))
+            }
+        }
+
+        let mut upload = file.value(ctx)?;
+        let file_name = std::mem::take(&mut upload.filename);
+        let upload_content_type = upload.content_type.take();
+
+        let mut content = Vec::new();
+        upload.into_read().read_to_end(&mut content)?;
+        let content_type = validate_document(upload_content_type.as_deref(), &content)?;
+
+        let id = Uuid::new_v4();
+        let storage_key = format!("applications/{}/{}", application_id, id);
+
+        storage
+            .put(&storage_key, content_type, &content)
+            .await
+            .map_err(|e| new_err("DOCUMENT_STORAGE_ERROR", &e.to_string()))?;
+
+        let document = application_documents::Model {
+            id,
+            application_id,
+            file_name,
+            content_type: content_type.to_string(),
+            size: content.len() as i64,
+            storage_key: storage_key.clone(),
+            uploaded_at: Utc::now(),
+        }
+        .into_active_model()
+        .insert(db)
+        .await;
+
+        match document {
+            Ok(document) => Ok(document),
+            Err(e) => {
+                // don't leave an orphaned file behind if the row couldn't be saved
+                if let Err(e) = storage.delete(&storage_key).await {
+                    event!(Level::ERROR, "Failed to delete orphaned document: {:?}", e);
+                }
+                Err(e.into())
+            }
+        }
+    }
+}
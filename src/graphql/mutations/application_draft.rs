@@ -0,0 +1,168 @@
+use async_graphql::{Context, Object};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, ModelTrait,
+    QueryFilter, QuerySelect, Select, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    applications::{
+        drafts::{draft_lifetime, ApplicationDraftInput, ApplicationDraftKind},
+        invalid_application_error, validate_application,
+    },
+    error::new_err,
+    graphql::types::{application_draft::ApplicationDraft, user::User},
+    guards::auth::AuthGuard,
+    schema::application_drafts,
+};
+
+use super::application::insert_validated_application;
+
+#[derive(Default)]
+pub struct ApplicationDraftMutation;
+
+/// Selects one of the user's drafts, as long as it hasn't expired
+fn select_draft(user: &User, draft_id: Uuid) -> Select<application_drafts::Entity> {
+    application_drafts::Entity::find_by_id(draft_id)
+        .filter(application_drafts::Column::UserId.eq(user.id))
+        .filter(application_drafts::Column::ExpiresAt.gt(Utc::now()))
+}
+
+fn draft_not_found() -> async_graphql::Error {
+    new_err("DRAFT_NOT_FOUND", "No application draft found with that id")
+}
+
+async fn find_draft(
+    db: &DatabaseConnection,
+    user: &User,
+    draft_id: Uuid,
+) -> async_graphql::Result<application_drafts::Model> {
+    select_draft(user, draft_id)
+        .one(db)
+        .await?
+        .ok_or_else(draft_not_found)
+}
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl ApplicationDraftMutation {
+    /// Saves a partially filled in application. Creates a new draft if no
+    /// `draft_id` is given, otherwise only the fields in `draft` are changed.
+    /// Saving a draft pushes back when it expires.
+    #[graphql(guard = "AuthGuard")]
+    async fn save_application_draft(
+        &self,
+        ctx: &Context<'_>,
+        draft_id: Option<Uuid>,
+        draft: ApplicationDraftInput,
+    ) -> async_graphql::Result<ApplicationDraft> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+        let now = Utc::now();
+
+        let existing = match draft_id {
+            Some(draft_id) => Some(find_draft(db, user, draft_id).await?),
+            None => None,
+        };
+
+        let current = existing
+            .as_ref()
+            .map(|model| {
+                ApplicationDraftKind::from_columns(&model.application_type, model.draft.clone())
+            })
+            .transpose()?;
+
+        let (application_type, value) = draft
+            .apply_to(current)
+            .ok_or_else(|| {
+                new_err(
+                    "INVALID_APPLICATION_DRAFT",
+                    "A draft can't be changed to a different type of application",
+                )
+            })?
+            .into_columns()?;
+
+        let model = match existing {
+            Some(model) => {
+                let mut model = model.into_active_model();
+                model.draft = Set(value);
+                model.updated_at = Set(now);
+                model.expires_at = Set(now + draft_lifetime());
+                model.update(db).await?
+            }
+            None => {
+                // Expired drafts are only hidden, so clear out the user's old ones
+                application_drafts::Entity::delete_many()
+                    .filter(application_drafts::Column::UserId.eq(user.id))
+                    .filter(application_drafts::Column::ExpiresAt.lte(now))
+                    .exec(db)
+                    .await?;
+
+                application_drafts::Model {
+                    id: Uuid::new_v4(),
+                    user_id: user.id,
+                    application_type,
+                    draft: value,
+                    created_at: now,
+                    updated_at: now,
+                    expires_at: now + draft_lifetime(),
+                }
+                .into_active_model()
+                .insert(db)
+                .await?
+            }
+        };
+
+        ApplicationDraft::try_from(model)
+    }
+
+    /// Submits a complete draft as an application, and deletes the draft in
+    /// the same transaction. Fails with the draft's `field_errors` if any
+    /// fields are missing or invalid.
+    #[graphql(guard = "AuthGuard")]
+    async fn submit_application_draft(
+        &self,
+        ctx: &Context<'_>,
+        draft_id: Uuid,
+    ) -> async_graphql::Result<Uuid> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        let txn = db.begin().await?;
+
+        // locked, so the same draft can't be submitted twice at once
+        let model = select_draft(user, draft_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(draft_not_found)?;
+
+        let application =
+            ApplicationDraftKind::from_columns(&model.application_type, model.draft.clone())?
+                .into_application()
+                .map_err(|field_errors| invalid_application_error(&field_errors))?;
+
+        validate_application(db, user, &application).await?;
+        let application_id = insert_validated_application(&txn, user, application).await?;
+
+        model.delete(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(application_id)
+    }
+
+    #[graphql(guard = "AuthGuard")]
+    async fn delete_application_draft(
+        &self,
+        ctx: &Context<'_>,
+        draft_id: Uuid,
+    ) -> async_graphql::Result<bool> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        find_draft(db, user, draft_id).await?.delete(db).await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,81 @@
+use async_graphql::{Context, Json, Object};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use serde_json::Value;
+
+use crate::{
+    applications::forms::{
+        compile_form_schema, validate_application_type, FormDefinition, BUILT_IN_APPLICATION_TYPES,
+    },
+    error::new_err,
+    graphql::types::{application_form::ApplicationForm, user::User},
+    guards::role::{RoleGuard, ADMIN_ROLE},
+    schema::application_forms,
+};
+
+#[derive(Default)]
+pub struct ApplicationFormMutation;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl ApplicationFormMutation {
+    /// Publishes a new version of an application form. Applications are
+    /// submitted against the latest version, and existing applications keep
+    /// the version they were submitted with.
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn publish_application_form(
+        &self,
+        ctx: &Context<'_>,
+        application_type: String,
+        title: String,
+        description: Option<String>,
+        schema: Json<Value>,
+    ) -> async_graphql::Result<ApplicationForm> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        validate_application_type(&application_type)?;
+
+        if BUILT_IN_APPLICATION_TYPES.contains(&application_type.as_str()) {
+            return Err(new_err(
+                "INVALID_APPLICATION_TYPE",
+                &format!("The {} form is built in", application_type),
+            ));
+        }
+
+        if title.trim().is_empty() {
+            return Err(new_err("INVALID_TITLE", "Form title must not be empty"));
+        }
+
+        compile_form_schema(&schema)?;
+
+        let txn = db.begin().await?;
+
+        let latest_version = application_forms::Entity::find()
+            .filter(application_forms::Column::ApplicationType.eq(&application_type))
+            .order_by_desc(application_forms::Column::Version)
+            .one(&txn)
+            .await?
+            .map(|form| form.version)
+            .unwrap_or(0);
+
+        let form = application_forms::Model {
+            application_type,
+            version: latest_version + 1,
+            title,
+            description,
+            schema: schema.0,
+            created_by: Some(user.id),
+            created_at: Utc::now(),
+        }
+        .into_active_model()
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(FormDefinition::from(form).into())
+    }
+}
@@ -0,0 +1,269 @@
+use async_graphql::{Context, Object};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QuerySelect, Set,
+    TransactionTrait,
+};
+use sendgrid::SGClient;
+use tracing::{event, Level};
+use uuid::Uuid;
+
+use crate::{
+    applications::{
+        events::{record_event, validate_event_message, NewApplicationEvent},
+        ApplicationKind, CitizenshipApplication, CitizenshipStatus,
+    },
+    citizens::grant_citizenship,
+    error::new_err,
+    graphql::types::{application::Application, user::User},
+    guards::role::{RoleGuard, REVIEWER_ROLE},
+    schema::{application_events, application_reviews, applications, users},
+};
+
+#[derive(Default)]
+pub struct ApplicationReviewMutation;
+
+async fn review_citizenship_application(
+    ctx: &Context<'_>,
+    application_id: Uuid,
+    status: CitizenshipStatus,
+    reason: Option<String>,
+) -> async_graphql::Result<Application> {
+    let db = ctx.data_unchecked::<DatabaseConnection>();
+    let reviewer = ctx.data_unchecked::<User>();
+
+    let txn = db.begin().await?;
+
+    let model = applications::Entity::find_by_id(application_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| new_err("APPLICATION_NOT_FOUND", "Application not found"))?;
+
+    let ApplicationKind::Citizenship(mut application) = ApplicationKind::try_from(&model)? else {
+        return Err(new_err(
+            "INVALID_APPLICATION_TYPE",
+            "Only citizenship applications can be reviewed",
+        ));
+    };
+
+    if model.user_id == reviewer.id {
+        return Err(new_err(
+            "FORBIDDEN",
+            "You cannot review your own application",
+        ));
+    }
+
+    if !application.citizenship_status.can_transition_to(status) {
+        return Err(new_err(
+            "INVALID_STATUS_TRANSITION",
+            &format!(
+                "Cannot change an application from {:?} to {:?}",
+                application.citizenship_status, status
+            ),
+        ));
+    }
+
+    application.citizenship_status = status;
+
+    let (_, application_json) = ApplicationKind::Citizenship(application.clone()).into_columns()?;
+    let applicant_id = model.user_id;
+    let mut active_model = model.into_active_model();
+    active_model.application = Set(application_json);
+    let model = active_model.update(&txn).await?;
+
+    application_reviews::Model {
+        id: Uuid::new_v4(),
+        application_id,
+        reviewer_id: Some(reviewer.id),
+        status,
+        reason: reason.clone(),
+        created_at: Utc::now(),
+    }
+    .into_active_model()
+    .insert(&txn)
+    .await?;
+
+    record_event(
+        &txn,
+        application_id,
+        NewApplicationEvent::status_changed(reviewer.id, status, reason.clone()),
+    )
+    .await?;
+
+    if status == CitizenshipStatus::Approved {
+        grant_citizenship(&txn, applicant_id, application_id).await?;
+    }
+
+    txn.commit().await?;
+
+    // The decision is already stored, so a failed email shouldn't fail the review
+    if let Err(e) = notify_applicant(ctx, applicant_id, &application, reason.as_deref()).await {
+        event!(Level::ERROR, "Failed to notify applicant: {:?}", e);
+    }
+
+    Application::try_from(model)
+}
+
+async fn notify_applicant(
+    ctx: &Context<'_>,
+    applicant_id: Uuid,
+    application: &CitizenshipApplication,
+    reason: Option<&str>,
+) -> async_graphql::Result<()> {
+    let db = ctx.data_unchecked::<DatabaseConnection>();
+    let s_g_client = ctx.data_unchecked::<SGClient>();
+
+    let applicant = users::Entity::find_by_id(applicant_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| new_err("USER_NOT_FOUND", "Applicant not found"))?;
+
+    let (subject, text) = match application.citizenship_status {
+        CitizenshipStatus::Approved => (
+            "Lumina: Your citizenship application has been approved",
+            "Congratulations, your application for Lumina citizenship has been approved!"
+                .to_string(),
+        ),
+        CitizenshipStatus::Rejected => (
+            "Lumina: Your citizenship application has been rejected",
+            format!(
+                "Unfortunately your application for Lumina citizenship has been rejected.\n\nReason: {}",
+                reason.unwrap_or("no reason was given")
+            ),
+        ),
+        CitizenshipStatus::InformationRequested => (
+            "Lumina: We need more information about your citizenship application",
+            format!(
+                "A reviewer needs more information to decide on your application for Lumina citizenship.\n\n{}",
+                reason.unwrap_or_default()
+            ),
+        ),
+        CitizenshipStatus::Pending => return Ok(()),
+    };
+
+    let mail = sendgrid::Mail::new()
+        .add_from("no-reply@lumina.earth")
+        .add_text(&text)
+        .add_subject(subject)
+        .add_to(sendgrid::Destination {
+            address: &applicant.email,
+            name: &applicant.first_name,
+        });
+
+    s_g_client
+        .send(mail)
+        .await
+        .map_err(|_| new_err("EMAIL_SEND_ERROR", "unable to send email"))?;
+
+    Ok(())
+}
+
+/// Adds a reviewer's comment or request to an application's timeline
+async fn add_reviewer_event(
+    ctx: &Context<'_>,
+    application_id: Uuid,
+    event: NewApplicationEvent,
+) -> async_graphql::Result<application_events::Model> {
+    let db = ctx.data_unchecked::<DatabaseConnection>();
+    let reviewer = ctx.data_unchecked::<User>();
+
+    let model = applications::Entity::find_by_id(application_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| new_err("APPLICATION_NOT_FOUND", "Application not found"))?;
+
+    if model.user_id == reviewer.id {
+        return Err(new_err(
+            "FORBIDDEN",
+            "You cannot review your own application",
+        ));
+    }
+
+    Ok(record_event(db, application_id, event).await?)
+}
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl ApplicationReviewMutation {
+    #[graphql(guard = "RoleGuard::new(REVIEWER_ROLE)")]
+    async fn approve_citizenship_application(
+        &self,
+        ctx: &Context<'_>,
+        application_id: Uuid,
+    ) -> async_graphql::Result<Application> {
+        review_citizenship_application(ctx, application_id, CitizenshipStatus::Approved, None).await
+    }
+
+    #[graphql(guard = "RoleGuard::new(REVIEWER_ROLE)")]
+    async fn reject_citizenship_application(
+        &self,
+        ctx: &Context<'_>,
+        application_id: Uuid,
+        reason: String,
+    ) -> async_graphql::Result<Application> {
+        review_citizenship_application(
+            ctx,
+            application_id,
+            CitizenshipStatus::Rejected,
+            Some(reason),
+        )
+        .await
+    }
+
+    /// Asks the applicant for more information before a decision is made
+    #[graphql(guard = "RoleGuard::new(REVIEWER_ROLE)")]
+    async fn request_citizenship_application_information(
+        &self,
+        ctx: &Context<'_>,
+        application_id: Uuid,
+        message: String,
+    ) -> async_graphql::Result<Application> {
+        review_citizenship_application(
+            ctx,
+            application_id,
+            CitizenshipStatus::InformationRequested,
+            Some(message),
+        )
+        .await
+    }
+
+    /// Comments on an application. Internal comments are only shown to
+    /// reviewers, other comments are shown to the applicant too.
+    #[graphql(guard = "RoleGuard::new(REVIEWER_ROLE)")]
+    async fn comment_on_application(
+        &self,
+        ctx: &Context<'_>,
+        application_id: Uuid,
+        comment: String,
+        #[graphql(default = false)] internal: bool,
+    ) -> async_graphql::Result<application_events::Model> {
+        let reviewer = ctx.data_unchecked::<User>();
+        validate_event_message(&comment)?;
+
+        add_reviewer_event(
+            ctx,
+            application_id,
+            NewApplicationEvent::comment_added(reviewer.id, comment, internal),
+        )
+        .await
+    }
+
+    /// Asks the applicant to upload a document, e.g. "A copy of your passport"
+    #[graphql(guard = "RoleGuard::new(REVIEWER_ROLE)")]
+    async fn request_application_document(
+        &self,
+        ctx: &Context<'_>,
+        application_id: Uuid,
+        document: String,
+    ) -> async_graphql::Result<application_events::Model> {
+        let reviewer = ctx.data_unchecked::<User>();
+        validate_event_message(&document)?;
+
+        add_reviewer_event(
+            ctx,
+            application_id,
+            NewApplicationEvent::document_requested(reviewer.id, document),
+        )
+        .await
+    }
+}
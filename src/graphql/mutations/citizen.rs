@@ -0,0 +1,108 @@
+use async_graphql::{Context, Object};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QuerySelect, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    citizens::{credentials::revoke_all_credentials, CitizenStatus},
+    error::new_err,
+    graphql::types::user::User,
+    guards::{
+        auth::AuthGuard,
+        role::{RoleGuard, ADMIN_ROLE},
+    },
+    schema::citizens,
+};
+
+#[derive(Default)]
+pub struct CitizenMutation;
+
+/// Changes the citizen's status, revoking their credentials in the same
+/// transaction unless they are reinstated
+async fn set_citizen_status(
+    ctx: &Context<'_>,
+    user_id: Uuid,
+    status: CitizenStatus,
+    reason: Option<String>,
+) -> async_graphql::Result<citizens::Model> {
+    let db = ctx.data_unchecked::<DatabaseConnection>();
+
+    let txn = db.begin().await?;
+
+    let citizen = citizens::Entity::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| new_err("CITIZEN_NOT_FOUND", "This user is not a citizen"))?;
+
+    if !citizen.status.can_transition_to(status) {
+        return Err(new_err(
+            "INVALID_STATUS_TRANSITION",
+            &format!(
+                "Cannot change a citizen from {:?} to {:?}",
+                citizen.status, status
+            ),
+        ));
+    }
+
+    let mut citizen = citizen.into_active_model();
+    citizen.status = Set(status);
+    citizen.status_reason = Set(reason);
+    citizen.updated_at = Set(Utc::now());
+    let citizen = citizen.update(&txn).await?;
+
+    // Reinstated citizens have to issue themselves new credentials
+    match status {
+        CitizenStatus::Active => {}
+        CitizenStatus::Suspended => {
+            revoke_all_credentials(&txn, user_id, "Citizenship suspended").await?;
+        }
+        CitizenStatus::Renounced => {
+            revoke_all_credentials(&txn, user_id, "Citizenship renounced").await?;
+        }
+    }
+
+    txn.commit().await?;
+
+    Ok(citizen)
+}
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl CitizenMutation {
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn suspend_citizen(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        reason: String,
+    ) -> async_graphql::Result<citizens::Model> {
+        set_citizen_status(ctx, user_id, CitizenStatus::Suspended, Some(reason)).await
+    }
+
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn reinstate_citizen(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        reason: Option<String>,
+    ) -> async_graphql::Result<citizens::Model> {
+        set_citizen_status(ctx, user_id, CitizenStatus::Active, reason).await
+    }
+
+    /// Gives up the current user's citizenship and revokes their
+    /// credentials. They can apply again later, and keep their citizenship
+    /// number if approved.
+    #[graphql(guard = "AuthGuard")]
+    async fn renounce_citizenship(
+        &self,
+        ctx: &Context<'_>,
+        reason: Option<String>,
+    ) -> async_graphql::Result<citizens::Model> {
+        let user = ctx.data_unchecked::<User>();
+
+        set_citizen_status(ctx, user.id, CitizenStatus::Renounced, reason).await
+    }
+}
@@ -0,0 +1,111 @@
+use async_graphql::{Context, Object};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use uuid::Uuid;
+
+use crate::{
+    citizens::{
+        credentials::{credential_validity, encode_credential, CredentialClaims},
+        find_citizen, format_citizenship_number, CitizenStatus,
+    },
+    error::new_err,
+    graphql::types::{citizen::IssuedCitizenshipCredential, user::User},
+    guards::{
+        auth::AuthGuard,
+        role::{has_role, ADMIN_ROLE},
+    },
+    schema::citizenship_credentials,
+    util::variables::SECRET_VARIABLES,
+};
+
+#[derive(Default)]
+pub struct CitizenshipCredentialMutation;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl CitizenshipCredentialMutation {
+    /// Issues a signed credential that lets third parties verify the user
+    /// is a citizen
+    #[graphql(guard = "AuthGuard")]
+    async fn issue_citizenship_credential(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<IssuedCitizenshipCredential> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        let citizen = find_citizen(db, user.id)
+            .await?
+            .filter(|citizen| citizen.status.is_citizen())
+            .ok_or_else(|| new_err("NOT_A_CITIZEN", "You are not a citizen"))?;
+
+        if citizen.status != CitizenStatus::Active {
+            return Err(new_err(
+                "CITIZENSHIP_SUSPENDED",
+                "Credentials can't be issued while your citizenship is suspended",
+            ));
+        }
+
+        let issued_at = Utc::now();
+        let expires_at = issued_at + credential_validity();
+
+        let model = citizenship_credentials::Model {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            issued_at,
+            expires_at,
+            revoked_at: None,
+            revocation_reason: None,
+        }
+        .into_active_model()
+        .insert(db)
+        .await?;
+
+        let claims = CredentialClaims::new(
+            model.id,
+            format_citizenship_number(citizen.number),
+            issued_at,
+            expires_at,
+        );
+        let credential = encode_credential(&claims, &SECRET_VARIABLES.credential_key)
+            .map_err(|e| new_err("COULD_NOT_CREATE_TOKEN", &format!("{}", e)))?;
+
+        Ok(IssuedCitizenshipCredential {
+            id: model.id,
+            credential,
+            issued_at: model.issued_at,
+            expires_at: model.expires_at,
+        })
+    }
+
+    /// Revokes one of your credentials, e.g. if it was leaked. Admins can
+    /// revoke anyone's credentials.
+    #[graphql(guard = "AuthGuard")]
+    async fn revoke_citizenship_credential(
+        &self,
+        ctx: &Context<'_>,
+        credential_id: Uuid,
+        reason: Option<String>,
+    ) -> async_graphql::Result<citizenship_credentials::Model> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        let credential = citizenship_credentials::Entity::find_by_id(credential_id)
+            .one(db)
+            .await?
+            .filter(|credential| credential.user_id == user.id || has_role(user, ADMIN_ROLE))
+            .ok_or_else(|| new_err("CREDENTIAL_NOT_FOUND", "Credential not found"))?;
+
+        if credential.revoked_at.is_some() {
+            return Err(new_err(
+                "CREDENTIAL_ALREADY_REVOKED",
+                "This credential has already been revoked",
+            ));
+        }
+
+        let mut credential = credential.into_active_model();
+        credential.revoked_at = Set(Some(Utc::now()));
+        credential.revocation_reason = Set(reason);
+
+        Ok(credential.update(db).await?)
+    }
+}
@@ -0,0 +1,340 @@
+use async_graphql::{Context, Object};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    assessments::rubric::{validate_rubric, Rubric},
+    courses::{
+        creates_prerequisite_cycle,
+        prerequisites::{
+            required_units, unit_prerequisite_graph, validate_prerequisites, UnitPrerequisiteInput,
+        },
+        set_prerequisites, validate_slug,
+    },
+    error::new_err,
+    guards::role::{RoleGuard, ADMIN_ROLE},
+    schema::{course_units, courses, unit_prerequisites, unit_questions},
+};
+
+#[derive(Default)]
+pub struct CourseMutation;
+
+fn course_not_found(slug: &str) -> async_graphql::Error {
+    new_err(
+        "COURSE_NOT_FOUND",
+        &format!("There is no course {:?}", slug),
+    )
+}
+
+fn validate_title(title: &str) -> async_graphql::Result<()> {
+    match title.trim().is_empty() {
+        true => Err(new_err("INVALID_TITLE", "Title must not be empty")),
+        false => Ok(()),
+    }
+}
+
+async fn set_published_at(
+    ctx: &Context<'_>,
+    slug: String,
+    published_at: Option<DateTime<Utc>>,
+) -> async_graphql::Result<courses::Model> {
+    let db = ctx.data_unchecked::<DatabaseConnection>();
+
+    let mut course = courses::Entity::find_by_id(slug.clone())
+        .one(db)
+        .await?
+        .ok_or_else(|| course_not_found(&slug))?
+        .into_active_model();
+
+    course.published_at = Set(published_at);
+    course.updated_at = Set(Utc::now());
+
+    Ok(course.update(db).await?)
+}
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl CourseMutation {
+    /// Creates or updates a course. New courses are unpublished.
+    /// Prerequisites are replaced if they are given. Learners have to
+    /// complete the published ones before completing any unit of the course.
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn save_course(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+        title: String,
+        description: Option<String>,
+        #[graphql(default = 0)] position: i32,
+        prerequisites: Option<Vec<String>>,
+    ) -> async_graphql::Result<courses::Model> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        validate_slug(&slug)?;
+        validate_title(&title)?;
+
+        let txn = db.begin().await?;
+
+        let course = courses::Entity::insert(
+            courses::Model {
+                slug: slug.clone(),
+                title,
+                description,
+                position,
+                published_at: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }
+            .into_active_model(),
+        )
+        .on_conflict(
+            OnConflict::column(courses::Column::Slug)
+                .update_columns([
+                    courses::Column::Title,
+                    courses::Column::Description,
+                    courses::Column::Position,
+                    courses::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(&txn)
+        .await?;
+
+        if let Some(prerequisites) = prerequisites {
+            set_prerequisites(&txn, &slug, prerequisites).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(course)
+    }
+
+    /// Publishes a course now, or schedules it to be published later
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn publish_course(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> async_graphql::Result<courses::Model> {
+        set_published_at(ctx, slug, Some(publish_at.unwrap_or_else(Utc::now))).await
+    }
+
+    /// Hides a course from learners. Their progress is kept.
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn unpublish_course(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+    ) -> async_graphql::Result<courses::Model> {
+        set_published_at(ctx, slug, None).await
+    }
+
+    /// Creates or updates a unit of a course
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn save_course_unit(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+        slug: String,
+        title: String,
+        description: Option<String>,
+        #[graphql(default = 0)] position: i32,
+    ) -> async_graphql::Result<course_units::Model> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        validate_slug(&slug)?;
+        validate_title(&title)?;
+
+        courses::Entity::find_by_id(course_slug.clone())
+            .one(db)
+            .await?
+            .ok_or_else(|| course_not_found(&course_slug))?;
+
+        Ok(course_units::Entity::insert(
+            course_units::Model {
+                course_slug,
+                slug,
+                title,
+                description,
+                position,
+            }
+            .into_active_model(),
+        )
+        .on_conflict(
+            OnConflict::columns([course_units::Column::CourseSlug, course_units::Column::Slug])
+                .update_columns([
+                    course_units::Column::Title,
+                    course_units::Column::Description,
+                    course_units::Column::Position,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await?)
+    }
+
+    /// Creates or updates a question in a unit. Answers are graded against
+    /// the rubric and compared with the reference answer, if they are given.
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn save_unit_question(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+        unit_slug: String,
+        slug: String,
+        question: String,
+        context: Option<String>,
+        reference_answer: Option<String>,
+        rubric: Option<Rubric>,
+        #[graphql(default = 0)] position: i32,
+    ) -> async_graphql::Result<unit_questions::Model> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        validate_slug(&slug)?;
+
+        if question.trim().is_empty() {
+            return Err(new_err("INVALID_QUESTION", "Question must not be empty"));
+        }
+
+        if let Some(rubric) = &rubric {
+            validate_rubric(rubric)?;
+        }
+
+        course_units::Entity::find_by_id((course_slug.clone(), unit_slug.clone()))
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                new_err(
+                    "UNIT_NOT_FOUND",
+                    &format!(
+                        "There is no unit {:?} in the course {:?}",
+                        unit_slug, course_slug
+                    ),
+                )
+            })?;
+
+        Ok(unit_questions::Entity::insert(
+            unit_questions::Model {
+                course_slug,
+                unit_slug,
+                slug,
+                question,
+                context,
+                reference_answer,
+                rubric,
+                position,
+            }
+            .into_active_model(),
+        )
+        .on_conflict(
+            OnConflict::columns([
+                unit_questions::Column::CourseSlug,
+                unit_questions::Column::UnitSlug,
+                unit_questions::Column::Slug,
+            ])
+            .update_columns([
+                unit_questions::Column::Question,
+                unit_questions::Column::Context,
+                unit_questions::Column::ReferenceAnswer,
+                unit_questions::Column::Rubric,
+                unit_questions::Column::Position,
+            ])
+            .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await?)
+    }
+
+    /// Replaces the rules a learner has to meet before completing a unit
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn set_unit_prerequisites(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+        unit_slug: String,
+        prerequisites: Vec<UnitPrerequisiteInput>,
+    ) -> async_graphql::Result<course_units::Model> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        let units = course_units::Entity::find()
+            .filter(course_units::Column::CourseSlug.eq(course_slug.clone()))
+            .all(db)
+            .await?;
+
+        let unit = units
+            .iter()
+            .find(|unit| unit.slug == unit_slug)
+            .cloned()
+            .ok_or_else(|| {
+                new_err(
+                    "UNIT_NOT_FOUND",
+                    &format!(
+                        "There is no unit {:?} in the course {:?}",
+                        unit_slug, course_slug
+                    ),
+                )
+            })?;
+
+        let prerequisites =
+            prerequisites
+                .into_iter()
+                .fold(Vec::new(), |mut unique, prerequisite| {
+                    if !unique.contains(&prerequisite) {
+                        unique.push(prerequisite);
+                    }
+                    unique
+                });
+        validate_prerequisites(&unit, &units, &prerequisites)?;
+
+        let txn = db.begin().await?;
+
+        let rules = unit_prerequisites::Entity::find()
+            .filter(unit_prerequisites::Column::CourseSlug.eq(course_slug.clone()))
+            .filter(unit_prerequisites::Column::UnitSlug.ne(unit_slug.clone()))
+            .all(&txn)
+            .await?;
+        let existing = unit_prerequisite_graph(&rules, &units);
+
+        let required_units = required_units(
+            &unit,
+            prerequisites
+                .iter()
+                .map(|prerequisite| (prerequisite.rule, prerequisite.unit_slug.as_ref())),
+            &units,
+        );
+
+        if creates_prerequisite_cycle(&existing, &unit_slug, &required_units) {
+            return Err(new_err(
+                "INVALID_PREREQUISITES",
+                "A unit can't be a prerequisite of itself",
+            ));
+        }
+
+        unit_prerequisites::Entity::delete_many()
+            .filter(unit_prerequisites::Column::CourseSlug.eq(course_slug.clone()))
+            .filter(unit_prerequisites::Column::UnitSlug.eq(unit_slug.clone()))
+            .exec(&txn)
+            .await?;
+
+        for prerequisite in prerequisites {
+            unit_prerequisites::Model {
+                id: Uuid::new_v4(),
+                course_slug: course_slug.clone(),
+                unit_slug: unit_slug.clone(),
+                rule: prerequisite.rule,
+                prerequisite_unit_slug: prerequisite.unit_slug,
+            }
+            .into_active_model()
+            .insert(&txn)
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(unit)
+    }
+}
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+
+use async_graphql::{Context, Object};
+use chrono::{Duration, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set, Unchanged};
+use sendgrid::SGClient;
+use tracing::{event, Level};
+
+use crate::{
+    error::new_err,
+    graphql::types::{user::User, Void},
+    guards::auth::AuthGuard,
+    schema::{email_verification_tokens, users},
+};
+
+lazy_static! {
+    static ref VERIFY_URL_BASE: url::Url =
+        url::Url::from_str("https://lumina.earth/verify-email").unwrap();
+}
+
+#[derive(Default)]
+pub struct EmailVerificationMutation;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl EmailVerificationMutation {
+    /// Emails the logged in user a link to verify their email address
+    #[graphql(guard = "AuthGuard")]
+    pub async fn send_email_verification(&self, ctx: &Context<'_>) -> async_graphql::Result<Void> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let s_g_client = ctx.data_unchecked::<SGClient>();
+        let user = ctx.data_unchecked::<User>();
+
+        if user.email_verified_at.is_some() {
+            return Err(new_err(
+                "EMAIL_ALREADY_VERIFIED",
+                "Your email address is already verified",
+            ));
+        }
+
+        let token = email_verification_tokens::Model {
+            id: uuid::Uuid::new_v4(),
+            user_id: user.id,
+            expires_at: Utc::now() + Duration::days(5),
+        }
+        .into_active_model()
+        .insert(db)
+        .await?;
+
+        let mut verify_url = VERIFY_URL_BASE.to_owned();
+        verify_url
+            .query_pairs_mut()
+            .append_pair("token", &token.id.as_simple().to_string());
+        let verify_text = format!("go to {} to verify your email address", verify_url);
+        let verify_mail = sendgrid::Mail::new()
+            .add_from("no-reply@lumina.earth")
+            .add_text(&verify_text)
+            .add_subject("Lumina: Verify your email address")
+            .add_to(sendgrid::Destination {
+                address: &user.email,
+                name: &user.first_name,
+            });
+
+        match s_g_client.send(verify_mail).await {
+            Ok(_) => Ok(Void),
+            Err(error) => {
+                event!(Level::ERROR, "{}", error);
+                Err(new_err("EMAIL_SEND_ERROR", "unable to send email"))
+            }
+        }
+    }
+
+    pub async fn verify_email(
+        &self,
+        ctx: &Context<'_>,
+        token_id: uuid::Uuid,
+    ) -> async_graphql::Result<Void> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        let token = email_verification_tokens::Entity::find_by_id(token_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| new_err("TOKEN_NOT_FOUND", "token doesn't exist"))?;
+
+        email_verification_tokens::Entity::delete_by_id(token.id)
+            .exec(db)
+            .await?;
+
+        if token.expires_at <= Utc::now() {
+            return Err(new_err(
+                "TOKEN_EXPIRED",
+                "token is expired, please request a new one.",
+            ));
+        }
+
+        users::ActiveModel {
+            id: Unchanged(token.user_id),
+            email_verified_at: Set(Some(Utc::now())),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+
+        Ok(Void)
+    }
+}
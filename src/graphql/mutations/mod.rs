@@ -1,9 +1,19 @@
 use async_graphql::MergedObject;
 
+mod ai_usage;
 mod application;
+mod application_document;
+mod application_draft;
+mod application_form;
+mod application_review;
 mod base;
+mod citizen;
+mod citizenship_credential;
+mod course;
+mod email_verification;
 mod password_reset;
 mod question_assessment;
+mod tutor;
 mod unit_progress;
 mod user;
 
@@ -11,8 +21,18 @@ mod user;
 pub struct Mutation(
     base::BaseMutation,
     user::UserMutation,
+    ai_usage::AiUsageMutation,
     application::ApplicationMutation,
+    application_document::ApplicationDocumentMutation,
+    application_draft::ApplicationDraftMutation,
+    application_form::ApplicationFormMutation,
+    application_review::ApplicationReviewMutation,
+    citizen::CitizenMutation,
+    citizenship_credential::CitizenshipCredentialMutation,
+    course::CourseMutation,
     question_assessment::QuestionAssessmentMutation,
+    tutor::TutorMutation,
     unit_progress::UnitProgressMutation,
     password_reset::PasswordResetMutation,
+    email_verification::EmailVerificationMutation,
 );
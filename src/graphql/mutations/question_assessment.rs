@@ -1,32 +1,37 @@
+use std::sync::Arc;
+
 use async_graphql::{Context, Object};
 use chrono::Utc;
-use openai::chat::{
-    ChatCompletionFunctionDefinition, ChatCompletionMessage, ChatCompletionMessageRole,
+use sea_orm::{
+    sea_query::Expr, ActiveEnum, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QuerySelect, Set, TransactionTrait,
 };
-use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait};
-use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
+    assessments::AssessmentProvider,
+    courses::grading::grade_answer,
     error::new_err,
     graphql::types::{
         question_assessment::{
-            QuestionAssessment, QuestionAssessmentActiveModel, QuestionAssessmentColumn,
-            QuestionAssessmentEntity,
+            QuestionAssessment, QuestionAssessmentColumn, QuestionAssessmentEntity,
         },
         user::User,
     },
-    guards::auth::AuthGuard,
-    schema::sea_orm_active_enums::Assessment,
+    guards::{
+        auth::AuthGuard,
+        role::{RoleGuard, TEACHER_ROLE},
+    },
+    schema::{question_assessment_overrides, question_attempts, sea_orm_active_enums::Assessment},
 };
 
-const MODEL: &str = "gpt-4-0613";
-
 #[derive(Default)]
 pub struct QuestionAssessmentMutation;
 
 #[Object(rename_fields = "snake_case", rename_args = "snake_case")]
 impl QuestionAssessmentMutation {
+    /// Grades the user's answer to a question. The question, its context
+    /// and how it is graded all come from the course, never the client.
     #[graphql(guard = "AuthGuard")]
     pub async fn question_assessment(
         &self,
@@ -34,135 +39,137 @@ impl QuestionAssessmentMutation {
         course_slug: String,
         unit_slug: String,
         question_slug: String,
-        question: String,
         answer: String,
-        question_context: Option<String>,
     ) -> async_graphql::Result<QuestionAssessment> {
         let user = ctx.data_unchecked::<User>();
         let conn = ctx.data_unchecked::<DatabaseConnection>();
+        let provider = ctx.data_unchecked::<Arc<dyn AssessmentProvider>>();
 
-        let messages = [
-            ChatCompletionMessage {
-                content: Some(format!(
-                    r#"
-- Assess the user's response/answer, and provide feedback and corrections in the function call feedback parameter.
-- If the answer is a SOFT_PASS or FAIL, explain how the answer can be improved.
-- Be strict and fail if the answer is not sufficient.
-- Use 'UNKNOWN' if the user did not answer the question.
-- Always provide constructive feedback.
-- Feedback can contain any markdown formatting (e.g. **bold**, *italics*, `code`, etc)
-- ALWAYS return the assessment function call with all parameters, even if the answer is UNKNOWN.
-
-Course Slug: {}
-Unit Slug: {}
-
-Question
-{}
-
-{}"#,
-                    course_slug,
-                    unit_slug,
-                    question,
-                    match question_context {
-                        Some(question_context) =>
-                            format!("Additional Context\n{}", question_context),
-                        None => String::new(),
-                    },
-                )),
-                name: None,
-                role: ChatCompletionMessageRole::System,
-                function_call: None,
-            },
-            ChatCompletionMessage {
-                content: Some(answer.clone()),
-                name: None,
-                role: ChatCompletionMessageRole::User,
-                function_call: None,
-            },
-        ];
-
-        let response = openai::chat::ChatCompletion::builder(MODEL, messages)
-            .functions([
-                ChatCompletionFunctionDefinition {
-                    name: "ai_assessment".into(),
-                    description: Some("Write an AI teacher assessment of the user's answer to a given question".into()),
-                    parameters: Some(serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "feedback": {
-                                "type": "string",
-                                "description": "AI assessment of the the user's response\nprovide feedback and corrections as markdown string"
-                            },
-                            "assessment": {
-                                "type": "string",
-                                "enum": ["PASS", "SOFT_PASS", "FAIL", "UNKNOWN"],
-                                "description": "Did the user accurately answer the question?"
-                            }
-                        },
-                        "required": ["assessment", "feedback"]
-                    })),
-                },
-            ])
-            .function_call(serde_json::json!({
-                "name": "ai_assessment"
-            }))
-            .user(slug::slugify(&user.first_name))
-            .create()
-            .await?;
-
-        let reply = &response.choices[0].message;
-
-        let function_call = reply.function_call.as_ref().ok_or_else(|| {
-            new_err(
-                "MISSING_FUNCTION_CALL",
-                "OpenAI did not return a function call in the response",
-            )
-        })?;
+        grade_answer(
+            conn,
+            provider.as_ref(),
+            user,
+            course_slug,
+            unit_slug,
+            question_slug,
+            answer,
+            None,
+        )
+        .await
+    }
 
-        #[derive(Deserialize)]
-        struct PartialAssessment {
-            feedback: String,
-            assessment: Assessment,
-        }
+    /// Asks a teacher to review the assessment of the user's latest answer
+    #[graphql(guard = "AuthGuard")]
+    pub async fn flag_question_assessment(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+        unit_slug: String,
+        question_slug: String,
+        reason: Option<String>,
+    ) -> async_graphql::Result<QuestionAssessment> {
+        let user = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
 
-        let partial_assessment =
-            serde_json::from_str::<PartialAssessment>(&function_call.arguments).map_err(|_| {
+        let assessment = QuestionAssessmentEntity::find()
+            .filter(QuestionAssessmentColumn::UserId.eq(user.id))
+            .filter(QuestionAssessmentColumn::CourseSlug.eq(course_slug))
+            .filter(QuestionAssessmentColumn::UnitSlug.eq(unit_slug))
+            .filter(QuestionAssessmentColumn::QuestionSlug.eq(question_slug))
+            .one(conn)
+            .await?
+            .ok_or_else(|| {
                 new_err(
-                    "INVALID_ARGUMENTS",
-                    "Failed to parse function call parameters from OpenAI",
+                    "ASSESSMENT_NOT_FOUND",
+                    "You haven't answered this question yet",
                 )
             })?;
 
-        let assessment: QuestionAssessmentActiveModel = QuestionAssessment {
+        if assessment.reviewed_at.is_some() {
+            return Err(new_err(
+                "ALREADY_REVIEWED",
+                "A teacher has already reviewed this answer",
+            ));
+        }
+
+        let mut active_model = assessment.into_active_model();
+        active_model.flagged_at = Set(Some(Utc::now()));
+        active_model.flag_reason = Set(reason.filter(|reason| !reason.trim().is_empty()));
+
+        Ok(active_model.update(conn).await?)
+    }
+
+    /// Replaces the grader's assessment and feedback with a teacher's, on
+    /// both the current assessment and the attempt it came from. The
+    /// original assessment is kept in the override's history.
+    #[graphql(guard = "RoleGuard::new(TEACHER_ROLE)")]
+    pub async fn override_question_assessment(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        assessment: Assessment,
+        feedback: String,
+    ) -> async_graphql::Result<QuestionAssessment> {
+        let reviewer = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let txn = conn.begin().await?;
+
+        let model = QuestionAssessmentEntity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| new_err("ASSESSMENT_NOT_FOUND", "Assessment not found"))?;
+
+        if model.user_id == reviewer.id {
+            return Err(new_err("FORBIDDEN", "You cannot review your own answers"));
+        }
+
+        question_assessment_overrides::Model {
             id: Uuid::new_v4(),
-            user_id: user.id,
-            course_slug,
-            unit_slug,
-            question_slug,
-            answer,
-            assessment: partial_assessment.assessment,
-            feedback: partial_assessment.feedback,
-            updated_at: Utc::now(),
+            question_assessment_id: model.id,
+            attempt: model.attempt,
+            reviewer_id: Some(reviewer.id),
+            previous_assessment: model.assessment,
+            previous_feedback: model.feedback.clone(),
+            assessment,
+            feedback: feedback.clone(),
+            created_at: Utc::now(),
         }
-        .into();
-
-        Ok(QuestionAssessmentEntity::insert(assessment)
-            .on_conflict(
-                OnConflict::columns([
-                    QuestionAssessmentColumn::UserId,
-                    QuestionAssessmentColumn::CourseSlug,
-                    QuestionAssessmentColumn::UnitSlug,
-                    QuestionAssessmentColumn::QuestionSlug,
-                ])
-                .update_columns([
-                    QuestionAssessmentColumn::Answer,
-                    QuestionAssessmentColumn::Assessment,
-                    QuestionAssessmentColumn::Feedback,
-                    QuestionAssessmentColumn::UpdatedAt,
-                ])
-                .to_owned(),
+        .into_active_model()
+        .insert(&txn)
+        .await?;
+
+        let reviewed_at = Utc::now();
+
+        question_attempts::Entity::update_many()
+            .col_expr(question_attempts::Column::Assessment, assessment.as_enum())
+            .col_expr(question_attempts::Column::Feedback, Expr::value(&feedback))
+            .col_expr(
+                question_attempts::Column::ReviewedBy,
+                Expr::value(reviewer.id),
             )
-            .exec_with_returning(conn)
-            .await?)
+            .col_expr(
+                question_attempts::Column::ReviewedAt,
+                Expr::value(reviewed_at),
+            )
+            .filter(question_attempts::Column::UserId.eq(model.user_id))
+            .filter(question_attempts::Column::CourseSlug.eq(model.course_slug.clone()))
+            .filter(question_attempts::Column::UnitSlug.eq(model.unit_slug.clone()))
+            .filter(question_attempts::Column::QuestionSlug.eq(model.question_slug.clone()))
+            .filter(question_attempts::Column::Attempt.eq(model.attempt))
+            .exec(&txn)
+            .await?;
+
+        let mut active_model = model.into_active_model();
+        active_model.assessment = Set(assessment);
+        active_model.feedback = Set(feedback);
+        active_model.reviewed_by = Set(Some(reviewer.id));
+        active_model.reviewed_at = Set(Some(reviewed_at));
+        let model = active_model.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(model)
     }
 }
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use async_graphql::{Context, Object};
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+use crate::{
+    assessments::AssessmentProvider, courses::tutor::send_tutor_message,
+    graphql::types::user::User, guards::auth::AuthGuard, schema::tutor_threads,
+};
+
+#[derive(Default)]
+pub struct TutorMutation;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl TutorMutation {
+    /// Asks the AI tutor about the assessment of the user's latest answer,
+    /// returning the thread with the tutor's reply
+    #[graphql(guard = "AuthGuard")]
+    pub async fn send_tutor_message(
+        &self,
+        ctx: &Context<'_>,
+        question_assessment_id: Uuid,
+        message: String,
+    ) -> async_graphql::Result<tutor_threads::Model> {
+        let user = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+        let provider = ctx.data_unchecked::<Arc<dyn AssessmentProvider>>();
+
+        send_tutor_message(
+            conn,
+            provider.as_ref(),
+            user,
+            question_assessment_id,
+            message,
+        )
+        .await
+    }
+}
@@ -1,4 +1,8 @@
 use crate::{
+    courses::{
+        certificates::issue_certificate_if_complete, find_unit,
+        prerequisites::ensure_prerequisites_met,
+    },
     graphql::types::unit_progress::{
         UnitProgressActiveModel, UnitProgressColumn, UnitProgressEntity,
     },
@@ -29,6 +33,12 @@ impl UnitProgressMutation {
         let user = ctx.data_unchecked::<User>();
         let conn = ctx.data_unchecked::<DatabaseConnection>();
 
+        let unit = find_unit(conn, &course_slug, &unit_slug).await?;
+
+        if status == UnitStatus::Completed {
+            ensure_prerequisites_met(conn, user.id, &unit).await?;
+        }
+
         let unit_progress: UnitProgressActiveModel = UnitProgress {
             id: Uuid::new_v4(),
             course_slug: course_slug.clone(),
@@ -39,7 +49,7 @@ impl UnitProgressMutation {
         }
         .into();
 
-        Ok(UnitProgressEntity::insert(unit_progress)
+        let unit_progress = UnitProgressEntity::insert(unit_progress)
             .on_conflict(
                 OnConflict::columns([
                     UnitProgressColumn::UserId,
@@ -50,6 +60,12 @@ impl UnitProgressMutation {
                 .to_owned(),
             )
             .exec_with_returning(conn)
-            .await?)
+            .await?;
+
+        if status == UnitStatus::Completed {
+            issue_certificate_if_complete(conn, user.id, &course_slug).await?;
+        }
+
+        Ok(unit_progress)
     }
 }
@@ -2,6 +2,7 @@ use crate::auth::Scope;
 use crate::error::new_err_with_detail;
 use crate::graphql::types::user::User;
 use crate::schema::users;
+use crate::util::phone::normalise_phone_number;
 use crate::util::variables::SECRET_VARIABLES;
 use crate::{
     auth::TokenPayload,
@@ -99,6 +100,7 @@ impl UserMutation {
         referrer: Option<Uuid>,
     ) -> async_graphql::Result<Uuid> {
         let email = email.trim().to_lowercase();
+        let phone = normalise_phone_number(&calling_code, &country_code, &phone_number)?;
         let user = User {
             id: Uuid::new_v4(),
             email,
@@ -106,12 +108,13 @@ impl UserMutation {
             password: bcrypt::hash(&password, bcrypt::DEFAULT_COST)?,
             first_name,
             last_name,
-            calling_code,
-            country_code,
-            phone_number,
+            calling_code: phone.calling_code,
+            country_code: phone.country_code,
+            phone_number: phone.e164,
             referrer,
             role: None,
             stripe_customer_id: None,
+            email_verified_at: None,
         };
 
         let active_model: users::ActiveModel = user.clone().into();
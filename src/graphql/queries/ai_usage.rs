@@ -0,0 +1,54 @@
+use async_graphql::{Context, Object};
+use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, DbBackend, FromQueryResult, Statement};
+
+use crate::{
+    graphql::types::ai_usage::{micro_usd_to_usd, AiCostReport, AiUserCost, AiUserCostRow},
+    guards::role::{RoleGuard, ADMIN_ROLE},
+};
+
+#[derive(Default)]
+pub struct AiUsageQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl AiUsageQuery {
+    /// What AI requests cost between `since` and `until` (or now)
+    #[graphql(guard = "RoleGuard::new(ADMIN_ROLE)")]
+    async fn ai_cost_report(
+        &self,
+        ctx: &Context<'_>,
+        since: DateTime<Utc>,
+        until: Option<DateTime<Utc>>,
+    ) -> async_graphql::Result<AiCostReport> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let rows = AiUserCostRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                ai_usage.user_id,
+                users.email,
+                count(*)::bigint AS requests,
+                sum(ai_usage.prompt_tokens)::bigint AS prompt_tokens,
+                sum(ai_usage.completion_tokens)::bigint AS completion_tokens,
+                sum(ai_usage.cost_micro_usd)::bigint AS cost_micro_usd
+            FROM ai_usage
+            LEFT JOIN users ON users.id = ai_usage.user_id
+            WHERE ai_usage.created_at >= $1 AND ai_usage.created_at < $2
+            GROUP BY ai_usage.user_id, users.email
+            ORDER BY cost_micro_usd DESC, requests DESC
+            "#,
+            [since.into(), until.unwrap_or_else(Utc::now).into()],
+        ))
+        .all(conn)
+        .await?;
+
+        Ok(AiCostReport {
+            requests: rows.iter().map(|row| row.requests).sum(),
+            prompt_tokens: rows.iter().map(|row| row.prompt_tokens).sum(),
+            completion_tokens: rows.iter().map(|row| row.completion_tokens).sum(),
+            cost_usd: micro_usd_to_usd(rows.iter().map(|row| row.cost_micro_usd).sum()),
+            users: rows.into_iter().map(AiUserCost::from).collect(),
+        })
+    }
+}
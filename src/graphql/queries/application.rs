@@ -0,0 +1,103 @@
+use async_graphql::{Context, InputObject, Object};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveEnum, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+use crate::{
+    applications::{
+        eligibility::{citizenship_eligibility_failures, EligibilityFailure},
+        CitizenshipApplicationInput, CitizenshipStatus,
+    },
+    error::new_err,
+    graphql::types::{application::Application, user::User},
+    guards::{
+        auth::AuthGuard,
+        role::{RoleGuard, REVIEWER_ROLE},
+    },
+    schema::applications,
+};
+
+const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(InputObject, Default)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CitizenshipApplicationFilter {
+    /// Defaults to pending applications
+    status: Option<CitizenshipStatus>,
+    country_of_residence: Option<String>,
+    submitted_after: Option<DateTime<Utc>>,
+    submitted_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+pub struct ApplicationQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl ApplicationQuery {
+    /// Checks whether the logged in user could submit this citizenship application.
+    /// Returns the rules it fails, or an empty list if they are eligible.
+    #[graphql(guard = "AuthGuard")]
+    async fn citizenship_eligibility(
+        &self,
+        ctx: &Context<'_>,
+        application: CitizenshipApplicationInput,
+    ) -> async_graphql::Result<Vec<EligibilityFailure>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        citizenship_eligibility_failures(conn, user, &application.into()).await
+    }
+
+    /// Citizenship applications waiting for a reviewer, oldest first
+    #[graphql(guard = "RoleGuard::new(REVIEWER_ROLE)")]
+    async fn citizenship_applications(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CitizenshipApplicationFilter>,
+        #[graphql(default = 50)] limit: u64,
+        #[graphql(default = 0)] offset: u64,
+    ) -> async_graphql::Result<Vec<Application>> {
+        if limit > MAX_PAGE_SIZE {
+            return Err(new_err(
+                "BAD_REQUEST",
+                &format!("Limit must be at most {}", MAX_PAGE_SIZE),
+            ));
+        }
+
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+        let filter = filter.unwrap_or_default();
+        let status = filter.status.unwrap_or(CitizenshipStatus::Pending);
+
+        let mut query = applications::Entity::find()
+            .filter(applications::Column::ApplicationType.eq("citizenship"))
+            .filter(Expr::cust_with_values(
+                "application->>'citizenship_status' = $1",
+                [status.to_value()],
+            ));
+
+        if let Some(country) = filter.country_of_residence {
+            query = query.filter(Expr::cust_with_values(
+                "application->>'country_of_residence' = $1",
+                [country],
+            ));
+        }
+        if let Some(after) = filter.submitted_after {
+            query = query.filter(applications::Column::CreatedAt.gte(after));
+        }
+        if let Some(before) = filter.submitted_before {
+            query = query.filter(applications::Column::CreatedAt.lt(before));
+        }
+
+        query
+            .order_by_asc(applications::Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(Application::try_from)
+            .collect()
+    }
+}
@@ -0,0 +1,54 @@
+use async_graphql::{Context, Object};
+use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder};
+
+use crate::{
+    applications::forms::{citizenship_form, find_form, FormDefinition},
+    graphql::types::application_form::ApplicationForm,
+    schema::application_forms,
+};
+
+#[derive(Default)]
+pub struct ApplicationFormQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl ApplicationFormQuery {
+    /// The latest version of every application form, so clients can render
+    /// them. Anyone can call this, without logging in.
+    async fn application_forms(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<ApplicationForm>> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        let stored = application_forms::Entity::find()
+            .order_by_asc(application_forms::Column::ApplicationType)
+            .order_by_desc(application_forms::Column::Version)
+            .all(db)
+            .await?;
+
+        let mut forms = vec![ApplicationForm::from(citizenship_form())];
+        for form in stored {
+            // versions are in descending order, so the first of each type is the latest
+            if forms.last().map(|last| &last.application_type) != Some(&form.application_type) {
+                forms.push(FormDefinition::from(form).into());
+            }
+        }
+
+        Ok(forms)
+    }
+
+    /// A version of an application form, or its latest version if no
+    /// version is given
+    async fn application_form(
+        &self,
+        ctx: &Context<'_>,
+        application_type: String,
+        version: Option<i32>,
+    ) -> async_graphql::Result<Option<ApplicationForm>> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(find_form(db, &application_type, version)
+            .await?
+            .map(ApplicationForm::from))
+    }
+}
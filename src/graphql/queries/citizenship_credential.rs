@@ -0,0 +1,95 @@
+use async_graphql::{Context, Object};
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    citizens::{
+        credentials::{decode_credential, CredentialError},
+        find_citizen, CitizenStatus,
+    },
+    graphql::types::citizen::{CredentialStatus, CredentialVerification, RevokedCredential},
+    schema::citizenship_credentials,
+    util::variables::SECRET_VARIABLES,
+};
+
+#[derive(Default)]
+pub struct CitizenshipCredentialQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl CitizenshipCredentialQuery {
+    /// Checks a citizenship credential presented by someone.
+    /// Anyone can call this, without logging in.
+    async fn verify_citizenship_credential(
+        &self,
+        ctx: &Context<'_>,
+        credential: String,
+    ) -> async_graphql::Result<CredentialVerification> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        let claims = match decode_credential(&credential, &SECRET_VARIABLES.credential_key) {
+            Ok(claims) => claims,
+            Err(CredentialError::Expired) => {
+                return Ok(CredentialVerification::rejected(CredentialStatus::Expired))
+            }
+            Err(CredentialError::Invalid) => {
+                return Ok(CredentialVerification::rejected(CredentialStatus::Invalid))
+            }
+        };
+
+        let stored = match citizenship_credentials::Entity::find_by_id(claims.jti)
+            .one(db)
+            .await?
+        {
+            Some(stored) => stored,
+            None => return Ok(CredentialVerification::rejected(CredentialStatus::Invalid)),
+        };
+
+        // credentials are only valid while the holder is an active citizen
+        let is_active_citizen = find_citizen(db, stored.user_id)
+            .await?
+            .is_some_and(|citizen| citizen.status == CitizenStatus::Active);
+
+        if stored.revoked_at.is_some() || !is_active_citizen {
+            return Ok(CredentialVerification::rejected(CredentialStatus::Revoked));
+        }
+
+        Ok(CredentialVerification {
+            status: CredentialStatus::Valid,
+            issued_at: Some(claims.issued_at()),
+            expires_at: Some(claims.expires_at()),
+            citizenship_number: Some(claims.sub),
+        })
+    }
+
+    /// The revocation list: ids of credentials that are no longer valid,
+    /// even though they haven't expired. Anyone can call this, without
+    /// logging in.
+    async fn revoked_citizenship_credentials(
+        &self,
+        ctx: &Context<'_>,
+        revoked_after: Option<DateTime<Utc>>,
+    ) -> async_graphql::Result<Vec<RevokedCredential>> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        let mut query = citizenship_credentials::Entity::find()
+            .filter(citizenship_credentials::Column::RevokedAt.is_not_null())
+            .filter(citizenship_credentials::Column::ExpiresAt.gt(Utc::now()));
+
+        if let Some(revoked_after) = revoked_after {
+            query = query.filter(citizenship_credentials::Column::RevokedAt.gt(revoked_after));
+        }
+
+        Ok(query
+            .order_by_asc(citizenship_credentials::Column::RevokedAt)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|credential| {
+                credential.revoked_at.map(|revoked_at| RevokedCredential {
+                    id: credential.id,
+                    revoked_at,
+                })
+            })
+            .collect())
+    }
+}
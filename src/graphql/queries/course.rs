@@ -0,0 +1,52 @@
+use async_graphql::{Context, Object};
+use chrono::Utc;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    courses::{is_published, published_condition},
+    graphql::types::user::User,
+    guards::role::{has_role, ADMIN_ROLE},
+    schema::courses,
+};
+
+/// Admins can see courses before they are published
+fn can_see_unpublished(ctx: &Context<'_>) -> bool {
+    ctx.data_opt::<User>()
+        .is_some_and(|user| has_role(user, ADMIN_ROLE))
+}
+
+#[derive(Default)]
+pub struct CourseQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl CourseQuery {
+    /// Every published course, in the order they are listed.
+    /// Anyone can call this, without logging in.
+    async fn courses(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<courses::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let mut query = courses::Entity::find();
+        if !can_see_unpublished(ctx) {
+            query = query.filter(published_condition());
+        }
+
+        Ok(query
+            .order_by_asc(courses::Column::Position)
+            .order_by_asc(courses::Column::Slug)
+            .all(conn)
+            .await?)
+    }
+
+    async fn course(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+    ) -> async_graphql::Result<Option<courses::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(courses::Entity::find_by_id(slug)
+            .one(conn)
+            .await?
+            .filter(|course| can_see_unpublished(ctx) || is_published(course, Utc::now())))
+    }
+}
@@ -0,0 +1,39 @@
+use async_graphql::{Context, Object};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{graphql::types::user::User, guards::auth::AuthGuard, schema::course_certificates};
+
+#[derive(Default)]
+pub struct CourseCertificateQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl CourseCertificateQuery {
+    /// Certificates for the courses the user has completed, newest first
+    #[graphql(guard = "AuthGuard")]
+    async fn course_certificates(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<course_certificates::Model>> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+        let user = ctx.data_unchecked::<User>();
+
+        Ok(course_certificates::Entity::find()
+            .filter(course_certificates::Column::UserId.eq(user.id))
+            .order_by_desc(course_certificates::Column::IssuedAt)
+            .all(db)
+            .await?)
+    }
+
+    /// Looks up a certificate by its id, or returns null if no such
+    /// certificate was issued. Anyone can call this, without logging in.
+    async fn verify_certificate(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+    ) -> async_graphql::Result<Option<course_certificates::Model>> {
+        let db = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(course_certificates::Entity::find_by_id(id).one(db).await?)
+    }
+}
@@ -1,8 +1,16 @@
 use async_graphql::MergedObject;
 
+mod ai_usage;
+mod application;
+mod application_form;
 mod auth_apps;
 mod base;
+mod citizenship_credential;
+mod course;
+mod course_certificate;
 mod question_assessment;
+mod stats;
+mod tutor;
 mod unit_progress;
 mod user;
 
@@ -10,7 +18,15 @@ mod user;
 pub struct Query(
     base::BaseQuery,
     user::UserQuery,
+    ai_usage::AiUsageQuery,
+    application::ApplicationQuery,
+    application_form::ApplicationFormQuery,
+    citizenship_credential::CitizenshipCredentialQuery,
+    course::CourseQuery,
+    course_certificate::CourseCertificateQuery,
     question_assessment::QuestionAssessmentQuery,
+    tutor::TutorQuery,
     unit_progress::UnitProgressQuery,
     auth_apps::AuthAppsQuery,
+    stats::StatsQuery,
 );
@@ -1,9 +1,15 @@
 use crate::{
+    error::new_err,
     graphql::types::question_assessment::{QuestionAssessmentColumn, QuestionAssessmentEntity},
-    guards::auth::AuthGuard,
+    guards::{
+        auth::AuthGuard,
+        role::{has_role, RoleGuard, TEACHER_ROLE},
+    },
+    schema::{question_assessment_overrides, question_attempts, sea_orm_active_enums::Assessment},
 };
 use async_graphql::{Context, Object};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
 
 use crate::graphql::types::{question_assessment::QuestionAssessment, user::User};
 
@@ -31,4 +37,85 @@ impl QuestionAssessmentQuery {
             .one(conn)
             .await?)
     }
+
+    /// Every answer given to a question, oldest first. Teachers can see
+    /// another learner's attempts by giving their `user_id`.
+    #[graphql(guard = "AuthGuard")]
+    async fn question_attempts(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+        unit_slug: String,
+        question_slug: String,
+        user_id: Option<Uuid>,
+    ) -> async_graphql::Result<Vec<question_attempts::Model>> {
+        let user = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let user_id = user_id.unwrap_or(user.id);
+        if user_id != user.id && !has_role(user, TEACHER_ROLE) {
+            return Err(new_err(
+                "FORBIDDEN",
+                "Only teachers can see other learners' attempts",
+            ));
+        }
+
+        Ok(question_attempts::Entity::find()
+            .filter(question_attempts::Column::UserId.eq(user_id))
+            .filter(question_attempts::Column::CourseSlug.eq(course_slug))
+            .filter(question_attempts::Column::UnitSlug.eq(unit_slug))
+            .filter(question_attempts::Column::QuestionSlug.eq(question_slug))
+            .order_by_asc(question_attempts::Column::Attempt)
+            .all(conn)
+            .await?)
+    }
+
+    /// Assessments waiting for a teacher, oldest first: those learners have
+    /// flagged, and soft passes, where the grader wasn't sure the answer was
+    /// good enough. Reviewed assessments leave the queue until the learner
+    /// answers again.
+    #[graphql(guard = "RoleGuard::new(TEACHER_ROLE)")]
+    async fn assessment_review_queue(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: Option<String>,
+    ) -> async_graphql::Result<Vec<QuestionAssessment>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let mut query = QuestionAssessmentEntity::find()
+            .filter(QuestionAssessmentColumn::ReviewedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(QuestionAssessmentColumn::FlaggedAt.is_not_null())
+                    .add(QuestionAssessmentColumn::Assessment.eq(Assessment::SoftPass)),
+            );
+
+        if let Some(course_slug) = course_slug {
+            query = query.filter(QuestionAssessmentColumn::CourseSlug.eq(course_slug));
+        }
+
+        Ok(query
+            .order_by_asc(QuestionAssessmentColumn::UpdatedAt)
+            .all(conn)
+            .await?)
+    }
+
+    /// Who changed an assessment, and what it was before, oldest first
+    #[graphql(guard = "RoleGuard::new(TEACHER_ROLE)")]
+    async fn question_assessment_overrides(
+        &self,
+        ctx: &Context<'_>,
+        question_assessment_id: Uuid,
+    ) -> async_graphql::Result<Vec<question_assessment_overrides::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(question_assessment_overrides::Entity::find()
+            .filter(
+                question_assessment_overrides::Column::QuestionAssessmentId
+                    .eq(question_assessment_id),
+            )
+            .order_by_asc(question_assessment_overrides::Column::CreatedAt)
+            .all(conn)
+            .await?)
+    }
 }
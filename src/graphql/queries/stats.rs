@@ -0,0 +1,171 @@
+use async_graphql::{Context, Object};
+use sea_orm::{DatabaseConnection, DbBackend, FromQueryResult, Statement};
+
+use crate::{
+    error::new_err,
+    graphql::types::stats::{StatsBucket, StatsGranularity},
+};
+
+const MAX_BUCKETS: i32 = 366;
+
+#[derive(Default)]
+pub struct StatsQuery;
+
+/// Counts the rows of `table` per bucket for the last `count` buckets,
+/// the most recent bucket being the one containing the current time.
+///
+/// `table`, `timestamp_column` and `filter` are interpolated into the SQL,
+/// so they must never come from user input.
+async fn bucketed_counts(
+    conn: &DatabaseConnection,
+    table: &str,
+    timestamp_column: &str,
+    filter: &str,
+    granularity: StatsGranularity,
+    count: i32,
+) -> async_graphql::Result<Vec<StatsBucket>> {
+    if !(1..=MAX_BUCKETS).contains(&count) {
+        return Err(new_err(
+            "BAD_REQUEST",
+            &format!("Count must be between 1 and {}", MAX_BUCKETS),
+        ));
+    }
+
+    // Everything is truncated in UTC so buckets don't depend on the session time zone
+    let sql = format!(
+        r#"
+        WITH counts AS (
+            SELECT date_trunc($1, "{timestamp_column}" AT TIME ZONE 'UTC') AS bucket, count(*) AS new
+            FROM "{table}"
+            WHERE {filter}
+            GROUP BY 1
+        ), series AS (
+            SELECT generate_series(
+                date_trunc($1, now() AT TIME ZONE 'UTC') - ($2 - 1) * ('1 ' || $1)::interval,
+                date_trunc($1, now() AT TIME ZONE 'UTC'),
+                ('1 ' || $1)::interval
+            ) AS bucket
+        )
+        SELECT
+            series.bucket AT TIME ZONE 'UTC' AS bucket,
+            coalesce(counts.new, 0)::bigint AS new,
+            (SELECT coalesce(sum(c.new), 0) FROM counts c WHERE c.bucket <= series.bucket)::bigint AS cumulative
+        FROM series
+        LEFT JOIN counts ON counts.bucket = series.bucket
+        ORDER BY series.bucket
+        "#
+    );
+
+    Ok(
+        StatsBucket::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            [granularity.as_sql().into(), count.into()],
+        ))
+        .all(conn)
+        .await?,
+    )
+}
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl StatsQuery {
+    /// New and cumulative user sign-ups over the last `count` days, weeks or months
+    async fn user_growth(
+        &self,
+        ctx: &Context<'_>,
+        granularity: StatsGranularity,
+        count: i32,
+    ) -> async_graphql::Result<Vec<StatsBucket>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        bucketed_counts(conn, "users", "joined", "true", granularity, count).await
+    }
+
+    /// New and cumulative citizenship applications over the last `count` days, weeks or months
+    async fn citizenship_application_stats(
+        &self,
+        ctx: &Context<'_>,
+        granularity: StatsGranularity,
+        count: i32,
+    ) -> async_graphql::Result<Vec<StatsBucket>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        bucketed_counts(
+            conn,
+            "applications",
+            "created_at",
+            "application_type = 'citizenship'",
+            granularity,
+            count,
+        )
+        .await
+    }
+
+    /// New and cumulative completed courses over the last `count` days, weeks or months
+    async fn course_completion_stats(
+        &self,
+        ctx: &Context<'_>,
+        granularity: StatsGranularity,
+        count: i32,
+    ) -> async_graphql::Result<Vec<StatsBucket>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        // certificates are issued once per course and never change, so the
+        // history doesn't shift when progress is updated later
+        bucketed_counts(
+            conn,
+            "course_certificates",
+            "completed_at",
+            "true",
+            granularity,
+            count,
+        )
+        .await
+    }
+
+    /// New and cumulative assessments reused from identical answers, rather
+    /// than graded by a model, over the last `count` days, weeks or months
+    async fn assessment_cache_stats(
+        &self,
+        ctx: &Context<'_>,
+        granularity: StatsGranularity,
+        count: i32,
+    ) -> async_graphql::Result<Vec<StatsBucket>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        bucketed_counts(
+            conn,
+            "question_attempts",
+            "created_at",
+            "cached",
+            granularity,
+            count,
+        )
+        .await
+    }
+
+    /// New and cumulative assessments graded by a model because no
+    /// identical answer could be reused, over the last `count` days, weeks
+    /// or months. Together with `assessment_cache_stats`, this gives the
+    /// cache's hit rate.
+    async fn assessment_cache_miss_stats(
+        &self,
+        ctx: &Context<'_>,
+        granularity: StatsGranularity,
+        count: i32,
+    ) -> async_graphql::Result<Vec<StatsBucket>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        // answers caught before grading have no hash, and were never
+        // looked up in the cache
+        bucketed_counts(
+            conn,
+            "question_attempts",
+            "created_at",
+            "answer_hash IS NOT NULL AND NOT cached",
+            granularity,
+            count,
+        )
+        .await
+    }
+}
@@ -0,0 +1,45 @@
+use async_graphql::{Context, Object};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use crate::{
+    error::new_err,
+    graphql::types::{question_assessment::QuestionAssessmentEntity, user::User},
+    guards::{
+        auth::AuthGuard,
+        role::{has_role, TEACHER_ROLE},
+    },
+    schema::tutor_threads,
+};
+
+#[derive(Default)]
+pub struct TutorQuery;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl TutorQuery {
+    /// The conversation with the AI tutor about an attempt at a question,
+    /// the latest attempt unless `attempt` is given. Teachers can read any
+    /// learner's threads.
+    #[graphql(guard = "AuthGuard")]
+    async fn tutor_thread(
+        &self,
+        ctx: &Context<'_>,
+        question_assessment_id: Uuid,
+        attempt: Option<i32>,
+    ) -> async_graphql::Result<Option<tutor_threads::Model>> {
+        let user = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let assessment = QuestionAssessmentEntity::find_by_id(question_assessment_id)
+            .one(conn)
+            .await?
+            .filter(|assessment| assessment.user_id == user.id || has_role(user, TEACHER_ROLE))
+            .ok_or_else(|| new_err("ASSESSMENT_NOT_FOUND", "Question assessment not found"))?;
+
+        Ok(tutor_threads::Entity::find()
+            .filter(tutor_threads::Column::QuestionAssessmentId.eq(assessment.id))
+            .filter(tutor_threads::Column::Attempt.eq(attempt.unwrap_or(assessment.attempt)))
+            .one(conn)
+            .await?)
+    }
+}
@@ -3,7 +3,12 @@ use std::collections::HashMap;
 use async_graphql::{Context, Object};
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
 
-use crate::{graphql::types::user::User, guards::auth::AuthGuard, schema::unit_progress};
+use crate::{
+    courses::progress::course_progress_summaries,
+    graphql::types::{course::CourseProgressSummary, user::User},
+    guards::auth::AuthGuard,
+    schema::unit_progress,
+};
 
 #[derive(Default)]
 pub struct UnitProgressQuery;
@@ -74,4 +79,34 @@ impl UnitProgressQuery {
             .one(conn)
             .await?)
     }
+
+    /// A summary of the user's progress through a published course, or
+    /// null if there is no such course
+    #[graphql(guard = "AuthGuard")]
+    pub async fn course_progress_summary(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+    ) -> async_graphql::Result<Option<CourseProgressSummary>> {
+        let user = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(course_progress_summaries(conn, user.id, Some(&course_slug))
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Summaries of every course the user has started, most recently
+    /// active first
+    #[graphql(guard = "AuthGuard")]
+    pub async fn course_progress_summaries(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<CourseProgressSummary>> {
+        let user = ctx.data_unchecked::<User>();
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(course_progress_summaries(conn, user.id, None).await?)
+    }
 }
@@ -0,0 +1,6 @@
+use async_graphql::MergedSubscription;
+
+mod question_assessment;
+
+#[derive(MergedSubscription, Default)]
+pub struct Subscription(question_assessment::QuestionAssessmentSubscription);
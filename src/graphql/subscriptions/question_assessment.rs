@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use async_graphql::{
+    futures_util::{stream, Stream},
+    Context, Subscription,
+};
+use sea_orm::DatabaseConnection;
+use tokio::sync::mpsc;
+
+use crate::{
+    assessments::AssessmentProvider,
+    courses::grading::grade_answer,
+    graphql::types::{question_assessment::QuestionAssessmentUpdate, user::User},
+    guards::auth::AuthGuard,
+};
+
+#[derive(Default)]
+pub struct QuestionAssessmentSubscription;
+
+#[Subscription(rename_fields = "snake_case", rename_args = "snake_case")]
+impl QuestionAssessmentSubscription {
+    /// Grades the user's answer to a question like the `question_assessment`
+    /// mutation, sending the feedback as it is written and then the stored
+    /// assessment
+    #[graphql(guard = "AuthGuard")]
+    async fn question_assessment(
+        &self,
+        ctx: &Context<'_>,
+        course_slug: String,
+        unit_slug: String,
+        question_slug: String,
+        answer: String,
+    ) -> impl Stream<Item = async_graphql::Result<QuestionAssessmentUpdate>> {
+        let user = ctx.data_unchecked::<User>().clone();
+        let conn = ctx.data_unchecked::<DatabaseConnection>().clone();
+        let provider = ctx.data_unchecked::<Arc<dyn AssessmentProvider>>().clone();
+
+        let (feedback, receiver) = mpsc::unbounded_channel();
+        let grading = tokio::spawn(async move {
+            grade_answer(
+                &conn,
+                provider.as_ref(),
+                &user,
+                course_slug,
+                unit_slug,
+                question_slug,
+                answer,
+                Some(&feedback),
+            )
+            .await
+        });
+
+        // the feedback, then the assessment once the sender is dropped
+        stream::unfold(Some((receiver, grading)), |state| async move {
+            let (mut receiver, grading) = state?;
+            if let Some(feedback) = receiver.recv().await {
+                let update = QuestionAssessmentUpdate {
+                    feedback: Some(feedback),
+                    assessment: None,
+                };
+                return Some((Ok(update), Some((receiver, grading))));
+            }
+
+            let update = match grading.await {
+                Ok(assessment) => assessment.map(|assessment| QuestionAssessmentUpdate {
+                    feedback: None,
+                    assessment: Some(assessment),
+                }),
+                Err(_) => Err("Grading the answer failed unexpectedly".into()),
+            };
+            Some((update, None))
+        })
+    }
+}
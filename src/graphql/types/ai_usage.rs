@@ -0,0 +1,69 @@
+use async_graphql::SimpleObject;
+use sea_orm::FromQueryResult;
+use uuid::Uuid;
+
+use crate::ai_usage::QuotaTier;
+
+/// Converts millionths of a US dollar to US dollars
+pub fn micro_usd_to_usd(micro_usd: i64) -> f64 {
+    micro_usd as f64 / 1_000_000.0
+}
+
+/// The user's AI requests today (UTC), and how many they have left
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct AiUsage {
+    pub tier: QuotaTier,
+    pub daily_quota: i32,
+    pub requests_today: i64,
+    pub remaining_today: i64,
+    pub prompt_tokens_today: i64,
+    pub completion_tokens_today: i64,
+    pub cost_today_usd: f64,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct AiUserCostRow {
+    pub user_id: Option<Uuid>,
+    pub email: Option<String>,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_micro_usd: i64,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct AiUserCost {
+    /// Missing if the user has since been deleted
+    pub user_id: Option<Uuid>,
+    pub email: Option<String>,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+}
+
+impl From<AiUserCostRow> for AiUserCost {
+    fn from(row: AiUserCostRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            email: row.email,
+            requests: row.requests,
+            prompt_tokens: row.prompt_tokens,
+            completion_tokens: row.completion_tokens,
+            cost_usd: micro_usd_to_usd(row.cost_micro_usd),
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct AiCostReport {
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+    /// Usage per user, most expensive first
+    pub users: Vec<AiUserCost>,
+}
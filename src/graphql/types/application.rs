@@ -1,3 +1,148 @@
-use crate::schema::applications::Model;
+use std::{sync::Arc, time::Duration};
 
-pub type Application = Model;
+use async_graphql::{ComplexObject, Context, SimpleObject};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{
+    applications::ApplicationKind,
+    error::new_err,
+    graphql::types::user::User,
+    guards::role::{has_role, REVIEWER_ROLE},
+    schema::{application_documents, application_events, application_reviews, applications::Model},
+    storage::DocumentStorage,
+};
+
+/// How long a document download URL stays valid for
+const DOCUMENT_URL_EXPIRY: Duration = Duration::from_secs(15 * 60);
+
+/// Only the applicant and reviewers may see an application's documents and
+/// timeline. Returns whether the viewer is a reviewer.
+fn ensure_can_view_details(
+    ctx: &Context<'_>,
+    applicant_id: Uuid,
+    details: &str,
+) -> async_graphql::Result<bool> {
+    let user = ctx.data_opt::<User>().ok_or_else(|| {
+        new_err(
+            "UNAUTHENTICATED",
+            &format!("You must be logged in to view application {}", details),
+        )
+    })?;
+
+    let is_reviewer = has_role(user, REVIEWER_ROLE);
+
+    match user.id == applicant_id || is_reviewer {
+        true => Ok(is_reviewer),
+        false => Err(new_err(
+            "FORBIDDEN",
+            &format!("You do not have permission to view these {}", details),
+        )),
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex, rename_fields = "snake_case")]
+pub struct Application {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub application: ApplicationKind,
+}
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl Application {
+    /// Decisions made by reviewers, oldest first
+    async fn reviews(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<application_reviews::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(application_reviews::Entity::find()
+            .filter(application_reviews::Column::ApplicationId.eq(self.id))
+            .order_by_asc(application_reviews::Column::CreatedAt)
+            .all(conn)
+            .await?)
+    }
+
+    /// Everything that has happened to the application, oldest first.
+    /// Internal comments are only included for reviewers.
+    async fn timeline(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<application_events::Model>> {
+        let is_reviewer = ensure_can_view_details(ctx, self.user_id, "events")?;
+
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let mut query = application_events::Entity::find()
+            .filter(application_events::Column::ApplicationId.eq(self.id));
+
+        if !is_reviewer {
+            query = query.filter(application_events::Column::Internal.eq(false));
+        }
+
+        Ok(query
+            .order_by_asc(application_events::Column::CreatedAt)
+            .all(conn)
+            .await?)
+    }
+
+    /// Documents uploaded by the applicant, oldest first
+    async fn documents(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<application_documents::Model>> {
+        ensure_can_view_details(ctx, self.user_id, "documents")?;
+
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(application_documents::Entity::find()
+            .filter(application_documents::Column::ApplicationId.eq(self.id))
+            .order_by_asc(application_documents::Column::UploadedAt)
+            .all(conn)
+            .await?)
+    }
+}
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl application_documents::Model {
+    /// A short-lived URL to download the document from, if the storage
+    /// backend supports it. Otherwise use `content`.
+    async fn download_url(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<String>> {
+        let storage = ctx.data_unchecked::<Arc<dyn DocumentStorage>>();
+
+        Ok(storage.download_url(&self.storage_key, DOCUMENT_URL_EXPIRY)?)
+    }
+
+    /// The document itself, base64 encoded
+    async fn content(&self, ctx: &Context<'_>) -> async_graphql::Result<String> {
+        let storage = ctx.data_unchecked::<Arc<dyn DocumentStorage>>();
+        let content = storage.get(&self.storage_key).await?;
+
+        Ok(base64::engine::general_purpose::STANDARD.encode(content))
+    }
+}
+
+impl TryFrom<Model> for Application {
+    type Error = async_graphql::Error;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        let application = ApplicationKind::try_from(&model).map_err(|e| {
+            new_err(
+                "INVALID_APPLICATION",
+                &format!("Stored application {} is invalid: {}", model.id, e),
+            )
+        })?;
+
+        Ok(Application {
+            id: model.id,
+            user_id: model.user_id,
+            created_at: model.created_at,
+            application,
+        })
+    }
+}
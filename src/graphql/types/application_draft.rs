@@ -0,0 +1,46 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    applications::drafts::{ApplicationDraftKind, FieldError},
+    error::new_err,
+    schema::application_drafts::Model,
+};
+
+/// An application that is still being filled in
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct ApplicationDraft {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Drafts that aren't saved again before this expire and are hidden
+    pub expires_at: DateTime<Utc>,
+    pub draft: ApplicationDraftKind,
+    /// Missing or invalid fields. The draft can be submitted once this is empty.
+    pub field_errors: Vec<FieldError>,
+}
+
+impl TryFrom<Model> for ApplicationDraft {
+    type Error = async_graphql::Error;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        let draft = ApplicationDraftKind::from_columns(&model.application_type, model.draft)
+            .map_err(|e| {
+                new_err(
+                    "INVALID_APPLICATION_DRAFT",
+                    &format!("Stored draft {} is invalid: {}", model.id, e),
+                )
+            })?;
+
+        Ok(ApplicationDraft {
+            id: model.id,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+            expires_at: model.expires_at,
+            field_errors: draft.field_errors(),
+            draft,
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use async_graphql::{Json, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::applications::forms::{FormDefinition, BUILT_IN_APPLICATION_TYPES};
+
+/// One version of the form for a type of application
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct ApplicationForm {
+    pub application_type: String,
+    pub version: i32,
+    pub title: String,
+    pub description: Option<String>,
+    /// The JSON Schema that applications are validated against
+    pub schema: Json<Value>,
+    /// Built-in forms are defined in code and can't be republished
+    pub built_in: bool,
+    /// When this version was published. Built-in forms have no date.
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<FormDefinition> for ApplicationForm {
+    fn from(form: FormDefinition) -> Self {
+        Self {
+            built_in: BUILT_IN_APPLICATION_TYPES.contains(&form.application_type.as_str()),
+            application_type: form.application_type,
+            version: form.version,
+            title: form.title,
+            description: form.description,
+            schema: Json(form.schema),
+            created_at: form.created_at,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use async_graphql::{ComplexObject, Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{citizens::format_citizenship_number, schema::citizens::Model};
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl Model {
+    /// The citizen's unique citizenship number, e.g. `L000000422`
+    async fn citizenship_number(&self) -> String {
+        format_citizenship_number(self.number)
+    }
+}
+
+/// A newly issued credential. The signed `credential` is only returned once,
+/// so the holder has to keep it.
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct IssuedCitizenshipCredential {
+    pub id: Uuid,
+    /// A signed JWT that proves the holder's citizenship
+    pub credential: String,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CredentialStatus {
+    Valid,
+    Expired,
+    Revoked,
+    /// The signature is wrong, or it isn't a citizenship credential
+    Invalid,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CredentialVerification {
+    pub status: CredentialStatus,
+    /// Only set if the credential is valid
+    pub citizenship_number: Option<String>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CredentialVerification {
+    pub fn rejected(status: CredentialStatus) -> Self {
+        Self {
+            status,
+            citizenship_number: None,
+            issued_at: None,
+            expires_at: None,
+        }
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(rename_fields = "snake_case")]
+pub struct RevokedCredential {
+    pub id: Uuid,
+    pub revoked_at: DateTime<Utc>,
+}
@@ -1,9 +1,120 @@
-use async_graphql::SimpleObject;
-use serde::{Deserialize, Serialize};
+use async_graphql::{ComplexObject, Context, SimpleObject};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
+};
 
-use super::unit_progress::UnitProgress;
+use crate::{
+    courses::{
+        certificates::{certificate_pdf, certificate_pdf_path},
+        is_published, published_condition,
+    },
+    schema::{
+        course_certificates, course_prerequisites, course_units, courses, unit_prerequisites,
+        unit_questions,
+    },
+};
 
-#[derive(Debug, Serialize, Deserialize, SimpleObject)]
-pub struct Course {
-    course_progress: UnitProgress,
+/// How far a user is through a course
+#[derive(Debug, Clone, SimpleObject, FromQueryResult)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CourseProgressSummary {
+    pub course_slug: String,
+    pub total_units: i64,
+    pub completed_units: i64,
+    pub in_progress_units: i64,
+    /// Completed units out of 100, rounded down
+    pub percent_complete: i32,
+    /// When the user last updated their progress in the course
+    pub last_activity_at: Option<DateTime<Utc>>,
+    /// The first unit the user hasn't completed, in course order
+    pub next_unit_slug: Option<String>,
+}
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl courses::Model {
+    async fn published(&self) -> bool {
+        is_published(self, Utc::now())
+    }
+
+    /// The course's units, in the order they should be taken
+    async fn units(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<course_units::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(course_units::Entity::find()
+            .filter(course_units::Column::CourseSlug.eq(self.slug.clone()))
+            .order_by_asc(course_units::Column::Position)
+            .order_by_asc(course_units::Column::Slug)
+            .all(conn)
+            .await?)
+    }
+
+    /// Published courses that have to be completed before any unit of this
+    /// one can be
+    async fn prerequisites(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<courses::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let slugs = course_prerequisites::Entity::find()
+            .filter(course_prerequisites::Column::CourseSlug.eq(self.slug.clone()))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|prerequisite| prerequisite.prerequisite_slug);
+
+        Ok(courses::Entity::find()
+            .filter(courses::Column::Slug.is_in(slugs))
+            .filter(published_condition())
+            .order_by_asc(courses::Column::Position)
+            .order_by_asc(courses::Column::Slug)
+            .all(conn)
+            .await?)
+    }
+}
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl course_units::Model {
+    /// What a learner has to do before they can complete this unit
+    async fn prerequisites(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<unit_prerequisites::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(unit_prerequisites::Entity::find()
+            .filter(unit_prerequisites::Column::CourseSlug.eq(self.course_slug.clone()))
+            .filter(unit_prerequisites::Column::UnitSlug.eq(self.slug.clone()))
+            .all(conn)
+            .await?)
+    }
+
+    async fn questions(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<unit_questions::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(unit_questions::Entity::find()
+            .filter(unit_questions::Column::CourseSlug.eq(self.course_slug.clone()))
+            .filter(unit_questions::Column::UnitSlug.eq(self.slug.clone()))
+            .order_by_asc(unit_questions::Column::Position)
+            .order_by_asc(unit_questions::Column::Slug)
+            .all(conn)
+            .await?)
+    }
+}
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl course_certificates::Model {
+    /// The certificate as a PDF, base64 encoded. Prefer downloading it
+    /// from `pdf_path`.
+    async fn pdf(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(certificate_pdf(self))
+    }
+
+    /// Where the PDF can be downloaded from with a `GET` request, relative
+    /// to the API's URL. Anyone with the link can download it.
+    async fn pdf_path(&self) -> String {
+        certificate_pdf_path(self.id)
+    }
 }
@@ -1,10 +1,16 @@
 use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
 
+pub mod ai_usage;
 pub mod application;
+pub mod application_draft;
+pub mod application_form;
 pub mod auth_apps;
+pub mod citizen;
 pub mod course;
 pub mod organisation;
 pub mod question_assessment;
+pub mod stats;
+pub mod tutor;
 pub mod unit_progress;
 pub mod user;
 
@@ -1,6 +1,16 @@
-use crate::schema::question_assessments::{ActiveModel, Column, Entity, Model};
+use async_graphql::SimpleObject;
+
+use crate::schema::question_assessments::{Column, Entity, Model};
 
 pub type QuestionAssessment = Model;
 pub type QuestionAssessmentEntity = Entity;
-pub type QuestionAssessmentActiveModel = ActiveModel;
 pub type QuestionAssessmentColumn = Column;
+
+/// Progress while an answer is being graded
+#[derive(SimpleObject)]
+pub struct QuestionAssessmentUpdate {
+    /// Feedback written since the last update
+    pub feedback: Option<String>,
+    /// The stored assessment, sent once grading is finished
+    pub assessment: Option<QuestionAssessment>,
+}
@@ -0,0 +1,32 @@
+use async_graphql::{Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use sea_orm::FromQueryResult;
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StatsGranularity {
+    Day,
+    Week,
+    Month,
+}
+
+impl StatsGranularity {
+    /// The unit name used by postgres in `date_trunc` and intervals
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            StatsGranularity::Day => "day",
+            StatsGranularity::Week => "week",
+            StatsGranularity::Month => "month",
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject, FromQueryResult)]
+#[graphql(rename_fields = "snake_case")]
+pub struct StatsBucket {
+    /// Start of the bucket (UTC)
+    pub bucket: DateTime<Utc>,
+    /// Number of records created within the bucket
+    pub new: i64,
+    /// Number of records created up to the end of the bucket
+    pub cumulative: i64,
+}
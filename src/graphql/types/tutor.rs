@@ -0,0 +1,31 @@
+use async_graphql::{ComplexObject, Context};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    courses::tutor::remaining_messages,
+    schema::{tutor_messages, tutor_threads},
+};
+
+#[ComplexObject(rename_fields = "snake_case", rename_args = "snake_case")]
+impl tutor_threads::Model {
+    /// The conversation, oldest first
+    async fn messages(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<tutor_messages::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(tutor_messages::Entity::find()
+            .filter(tutor_messages::Column::ThreadId.eq(self.id))
+            .order_by_asc(tutor_messages::Column::CreatedAt)
+            .all(conn)
+            .await?)
+    }
+
+    /// How many more messages the learner can send in this thread
+    async fn remaining_messages(&self, ctx: &Context<'_>) -> async_graphql::Result<u64> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(remaining_messages(conn, self.id).await?)
+    }
+}
@@ -1,20 +1,26 @@
 use std::str::FromStr;
 
 use crate::{
-    applications::{CitizenshipApplication, CitizenshipStatus},
+    ai_usage::{daily_quota, start_of_day, usage_since},
+    citizens::{find_citizen, CitizenStatus},
     error::new_err,
+    graphql::types::{
+        ai_usage::{micro_usd_to_usd, AiUsage},
+        application::Application,
+        application_draft::ApplicationDraft,
+    },
     guards::scope::ScopeGuard,
-    schema::users,
-    util::{stripe::get_stripe_client, variables::SECRET_VARIABLES},
+    schema::{citizens, citizenship_credentials, users},
+    util::stripe::{get_stripe_client, light_university_subscription},
 };
 use async_graphql::{ComplexObject, Context, Enum, SimpleObject};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use sea_orm::{
-    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
-    PaginatorTrait, QueryFilter, QueryOrder, Set, Unchanged,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set, Unchanged,
 };
 use serde::{Deserialize, Serialize};
-use stripe::{CreateBillingPortalSession, PriceId};
+use stripe::CreateBillingPortalSession;
 
 pub type User = users::Model;
 pub type UserActiveModel = users::ActiveModel;
@@ -61,27 +67,89 @@ impl User {
     async fn citizenship_status(
         &self,
         ctx: &Context<'_>,
-    ) -> async_graphql::Result<Option<CitizenshipStatus>> {
+    ) -> async_graphql::Result<Option<CitizenStatus>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(find_citizen(conn, self.id)
+            .await?
+            .map(|citizen| citizen.status))
+    }
+
+    /// The user's citizen record, if they have ever been granted citizenship
+    #[graphql(guard = "ScopeGuard::new(\"citizenship:read:citizen\")")]
+    async fn citizen(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<citizens::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(find_citizen(conn, self.id).await?)
+    }
+
+    /// Credentials issued to the user, newest first
+    #[graphql(guard = "ScopeGuard::new(\"citizenship:read:citizen\")")]
+    async fn citizenship_credentials(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<citizenship_credentials::Model>> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        Ok(self
+            .find_related(citizenship_credentials::Entity)
+            .order_by_desc(citizenship_credentials::Column::IssuedAt)
+            .all(conn)
+            .await?)
+    }
+
+    #[graphql(guard = "ScopeGuard::new(\"applications:read\")")]
+    async fn applications(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Application>> {
         use crate::schema::applications;
 
         let conn = ctx.data_unchecked::<DatabaseConnection>();
 
-        match applications::Entity::find()
-            .filter(applications::Column::ApplicationType.eq("citizenship"))
-            .filter(Expr::cust_with_expr(
-                "application->>'user_id' = $1",
-                self.id.to_string(),
-            ))
+        self.find_related(applications::Entity)
             .order_by_desc(applications::Column::CreatedAt)
-            .one(conn)
+            .all(conn)
             .await?
-        {
-            Some(application) => {
-                let json: CitizenshipApplication = serde_json::from_value(application.application)?;
-                Ok(Some(json.citizenship_status))
-            }
-            None => Ok(None),
-        }
+            .into_iter()
+            .map(Application::try_from)
+            .collect()
+    }
+
+    /// Applications the user hasn't submitted yet, most recently saved first
+    #[graphql(guard = "ScopeGuard::new(\"applications:read\")")]
+    async fn application_drafts(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<ApplicationDraft>> {
+        use crate::schema::application_drafts;
+
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        self.find_related(application_drafts::Entity)
+            .filter(application_drafts::Column::ExpiresAt.gt(Utc::now()))
+            .order_by_desc(application_drafts::Column::UpdatedAt)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(ApplicationDraft::try_from)
+            .collect()
+    }
+
+    /// The user's AI requests today, against their daily quota
+    #[graphql(guard = "ScopeGuard::new(\"billing\")")]
+    async fn ai_usage(&self, ctx: &Context<'_>) -> async_graphql::Result<AiUsage> {
+        let conn = ctx.data_unchecked::<DatabaseConnection>();
+
+        let (tier, daily_quota) = daily_quota(conn, self).await?;
+        let usage = usage_since(conn, self.id, start_of_day()).await?;
+
+        Ok(AiUsage {
+            tier,
+            daily_quota,
+            requests_today: usage.requests,
+            remaining_today: (i64::from(daily_quota) - usage.requests).max(0),
+            prompt_tokens_today: usage.prompt_tokens,
+            completion_tokens_today: usage.completion_tokens,
+            cost_today_usd: micro_usd_to_usd(usage.cost_micro_usd),
+        })
     }
 
     #[graphql(guard = "ScopeGuard::new(\"billing\")")]
@@ -108,22 +176,7 @@ impl User {
         ctx: &Context<'_>,
     ) -> async_graphql::Result<SubscriptionInfo> {
         let stripe_customer_id = self.stripe_customer_id(ctx).await?;
-        let client = get_stripe_client();
-
-        let subscription = stripe::Subscription::list(
-            &client,
-            &stripe::ListSubscriptions {
-                customer: Some(stripe::CustomerId::from_str(&stripe_customer_id)?),
-                price: Some(PriceId::from_str(
-                    &SECRET_VARIABLES.light_university_product_id,
-                )?),
-                ..Default::default()
-            },
-        )
-        .await?
-        .data
-        .get(0)
-        .cloned();
+        let subscription = light_university_subscription(&stripe_customer_id).await?;
 
         match subscription {
             Some(subscription) => {
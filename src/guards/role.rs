@@ -0,0 +1,48 @@
+use async_graphql::{async_trait::async_trait, Context, Guard, Result};
+
+use crate::{error::new_err, graphql::types::user::User};
+
+/// Admins are allowed to do anything any other role can do
+pub const ADMIN_ROLE: &str = "admin";
+pub const REVIEWER_ROLE: &str = "reviewer";
+pub const TEACHER_ROLE: &str = "teacher";
+
+/// Whether the user has the role, or is an admin
+pub fn has_role(user: &User, role: &str) -> bool {
+    matches!(user.role.as_deref(), Some(user_role) if user_role == role || user_role == ADMIN_ROLE)
+}
+
+pub struct RoleGuard {
+    required_role: String,
+}
+
+impl RoleGuard {
+    pub fn new<T: Into<String>>(required_role: T) -> Self {
+        Self {
+            required_role: required_role.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let user = ctx.data_opt::<User>().ok_or_else(|| {
+            new_err(
+                "UNAUTHENTICATED",
+                "You must be logged in to perform this action",
+            )
+        })?;
+
+        match has_role(user, &self.required_role) {
+            true => Ok(()),
+            false => Err(new_err(
+                "FORBIDDEN",
+                &format!(
+                    "You need the {} role to perform this action",
+                    self.required_role
+                ),
+            )),
+        }
+    }
+}
@@ -1,26 +1,59 @@
+pub(crate) mod ai_usage;
 pub(crate) mod applications;
+pub(crate) mod assessments;
 pub(crate) mod auth;
+pub(crate) mod citizens;
+pub(crate) mod courses;
+pub mod data_migrations;
 pub(crate) mod error;
 pub(crate) mod graphql;
 pub(crate) mod guards;
 pub mod schema;
+pub(crate) mod storage;
 pub(crate) mod util;
 
 use std::{future::Future, pin::Pin, sync::Arc};
 
-use async_graphql::{EmptySubscription, Schema};
+use applications::documents::MAX_DOCUMENT_SIZE;
+use assessments::{assessment_provider_from_env, AssessmentProvider};
+use async_graphql::{
+    futures_util::{
+        stream::{self, BoxStream},
+        StreamExt,
+    },
+    http::MultipartOptions,
+    Schema,
+};
 use auth::authenticate_request;
-use graphql::{mutations::Mutation, queries::Query};
+use citizens::credentials::CREDENTIAL_KEYS_PATH;
+use courses::certificates::{certificate_id_from_path, certificate_pdf};
+use graphql::{mutations::Mutation, queries::Query, subscriptions::Subscription};
 use lambda_http::{http::Method, Body, Error, Request, Response, Service};
-use sea_orm::{Database, DatabaseConnection};
-pub use util::variables::SECRET_VARIABLES;
+use schema::course_certificates;
+use sea_orm::{Database, DatabaseConnection, EntityTrait};
 use sendgrid::SGClient;
+use storage::{storage_from_env, DocumentStorage};
+pub use util::variables::SECRET_VARIABLES;
+
+const EVENT_STREAM: &str = "text/event-stream";
+
+/// Whether the client asked for a stream of server-sent events, rather than
+/// a single JSON response
+pub fn wants_event_stream(event: &Request) -> bool {
+    event
+        .headers()
+        .get("accept")
+        .and_then(|header| header.to_str().ok())
+        .is_some_and(|accept| accept.contains(EVENT_STREAM))
+}
 
 #[derive(Clone)]
 pub struct App {
-    schema: Arc<Schema<Query, Mutation, EmptySubscription>>,
+    schema: Arc<Schema<Query, Mutation, Subscription>>,
     db: DatabaseConnection,
     sendgrid_client: sendgrid::SGClient,
+    storage: Arc<dyn DocumentStorage>,
+    assessment_provider: Arc<dyn AssessmentProvider>,
 }
 
 impl App {
@@ -39,7 +72,7 @@ impl App {
             schema: Arc::new(Schema::new(
                 Query::default(),
                 Mutation::default(),
-                EmptySubscription,
+                Subscription::default(),
             )),
             db: Database::connect(match test_database_url {
                 Some(url) => url,
@@ -50,6 +83,8 @@ impl App {
             })
             .await?,
             sendgrid_client: SGClient::new(&SECRET_VARIABLES.sendgrid_api_key),
+            storage: storage_from_env()?,
+            assessment_provider: assessment_provider_from_env()?,
         })
     }
 
@@ -59,10 +94,11 @@ impl App {
 
         match *event.method() {
             Method::OPTIONS => self.handle_options().await,
+            Method::GET => self.handle_get(event).await,
             Method::POST => self.handle_post(event).await,
             _ => response
                 .status(405)
-                .header("Allow", "POST, OPTIONS")
+                .header("Allow", "GET, POST, OPTIONS")
                 .body("405: Method not allowed - use POST instead.".into())
                 .map_err(Error::from),
         }
@@ -75,38 +111,147 @@ impl App {
         response
             .status(200)
             .header("Access-Control-Allow-Origin", "*")
-            .header("Access-Control-Allow-Methods", "POST")
+            .header("Access-Control-Allow-Methods", "GET, POST")
             .header("Access-Control-Allow-Headers", "*")
             .body(Body::Empty)
             .map_err(Error::from)
     }
 
-    async fn graph_endpoint(
+    /// Files that are served outside of GraphQL, e.g. the public keys that
+    /// citizenship credentials are signed with
+    async fn handle_get(&self, event: Request) -> Result<Response<Body>, Error> {
+        let response = Response::builder();
+
+        if let Some(id) = certificate_id_from_path(event.uri().path()) {
+            return match course_certificates::Entity::find_by_id(id)
+                .one(&self.db)
+                .await?
+            {
+                Some(certificate) => response
+                    .status(200)
+                    .header("content-type", "application/pdf")
+                    .header(
+                        "content-disposition",
+                        format!("inline; filename=\"certificate-{}.pdf\"", certificate.id),
+                    )
+                    .header("Access-Control-Allow-Origin", "*")
+                    .body(certificate_pdf(&certificate).into())
+                    .map_err(Error::from),
+                None => response
+                    .status(404)
+                    .body("404: Not found".into())
+                    .map_err(Error::from),
+            };
+        }
+
+        match event.uri().path() {
+            CREDENTIAL_KEYS_PATH => response
+                .status(200)
+                .header("content-type", "application/json")
+                .header("cache-control", "public, max-age=3600")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(serde_json::to_string(&SECRET_VARIABLES.credential_key.jwk_set())?.into())
+                .map_err(Error::from),
+            _ => response
+                .status(404)
+                .body("404: Not found".into())
+                .map_err(Error::from),
+        }
+    }
+
+    /// The GraphQL request in `event`, or the response to send instead if
+    /// the request can't be authenticated
+    async fn graphql_request(
         &self,
         event: Request,
-    ) -> Result<async_graphql::Response, anyhow::Error> {
-        let body = std::str::from_utf8(event.body())?;
-        let mut graphql_request = serde_json::from_str::<async_graphql::Request>(body)?
-            .data(self.db.clone())
-            .data(self.sendgrid_client.clone());
-
-        match authenticate_request(&self.db, event).await {
-            Ok(Some((user, scopes))) => graphql_request = graphql_request.data(user).data(scopes),
-            Ok(None) => {}
+    ) -> Result<Result<async_graphql::Request, async_graphql::Response>, anyhow::Error> {
+        let content_type = event
+            .headers()
+            .get("content-type")
+            .and_then(|header| header.to_str().ok());
+
+        // JSON requests, or multipart requests for file uploads
+        let graphql_request = async_graphql::http::receive_body(
+            content_type,
+            event.body().as_ref(),
+            MultipartOptions::default()
+                .max_file_size(MAX_DOCUMENT_SIZE)
+                .max_num_files(1),
+        )
+        .await?
+        .data(self.db.clone())
+        .data(self.sendgrid_client.clone())
+        .data(self.storage.clone())
+        .data(self.assessment_provider.clone());
+
+        Ok(match authenticate_request(&self.db, event).await {
+            Ok(Some((user, scopes))) => Ok(graphql_request.data(user).data(scopes)),
+            Ok(None) => Ok(graphql_request),
             Err(e) => {
-                return Ok(async_graphql::Response::from_errors(vec![
-                    e.into_server_error(async_graphql::Pos { line: 0, column: 0 })
-                ]))
+                Err(async_graphql::Response::from_errors(vec![e
+                    .into_server_error(async_graphql::Pos {
+                        line: 0,
+                        column: 0,
+                    })]))
             }
+        })
+    }
+
+    async fn graph_endpoint(
+        &self,
+        event: Request,
+    ) -> Result<async_graphql::Response, anyhow::Error> {
+        Ok(match self.graphql_request(event).await? {
+            Ok(graphql_request) => self.schema.execute(graphql_request).await,
+            Err(response) => response,
+        })
+    }
+
+    /// Executes the request as a stream of server-sent events, in the
+    /// "distinct connections" mode of the GraphQL over SSE protocol: a
+    /// `next` event for each response, then a `complete` event. This is how
+    /// clients run subscriptions, though any operation can be sent.
+    pub async fn event_stream(
+        &self,
+        event: Request,
+    ) -> Result<BoxStream<'static, String>, anyhow::Error> {
+        let responses = match self.graphql_request(event).await? {
+            Ok(graphql_request) => self.schema.execute_stream(graphql_request).boxed(),
+            Err(response) => stream::once(async { response }).boxed(),
         };
-        let res = self.schema.execute(graphql_request).await;
 
-        Ok(res)
+        Ok(responses
+            .map(|response| {
+                format!(
+                    "event: next\ndata: {}\n\n",
+                    serde_json::to_string(&response).unwrap_or_default()
+                )
+            })
+            .chain(stream::once(async {
+                "event: complete\ndata: \n\n".to_string()
+            }))
+            .boxed())
     }
 
     async fn handle_post(&self, event: Request) -> Result<Response<Body>, Error> {
         let response = Response::builder();
 
+        // Lambda can't stream responses, so the events are sent all at once
+        // when the operation is finished. Only the stream_server binary,
+        // deployed separately, sends them as they happen.
+        if wants_event_stream(&event) {
+            let events = self.event_stream(event).await?.collect::<Vec<_>>().await;
+
+            return response
+                .status(200)
+                .header("content-type", EVENT_STREAM)
+                .header("Access-Control-Allow-Origin", "*")
+                .header("Access-Control-Allow-Methods", "POST")
+                .header("Access-Control-Allow-Headers", "*")
+                .body(events.concat().into())
+                .map_err(Error::from);
+        }
+
         let graphql_response = self.graph_endpoint(event).await?;
 
         let json = serde_json::to_string(&graphql_response)?;
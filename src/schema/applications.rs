@@ -1,38 +1,69 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
-use async_graphql::{InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
 use sea_orm::{
     ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait,
-    EnumIter, PrimaryKeyTrait,
+    EnumIter, PrimaryKeyTrait, Related, RelationDef, RelationTrait,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(
-    Clone,
-    Debug,
-    PartialEq,
-    DeriveEntityModel,
-    Eq,
-    SimpleObject,
-    InputObject,
-    Serialize,
-    Deserialize,
-)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "applications")]
-#[graphql]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
     #[serde(with = "chrono::serde::ts_milliseconds")]
     pub created_at: DateTime<Utc>,
     #[sea_orm(column_type = "JsonBinary")]
     pub application: Value,
     pub application_type: String,
+    /// The version of the application's form, for forms defined in the
+    /// database. Built-in forms have no version.
+    pub form_version: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::application_documents::Entity")]
+    ApplicationDocuments,
+    #[sea_orm(has_many = "super::application_events::Entity")]
+    ApplicationEvents,
+    #[sea_orm(has_many = "super::application_reviews::Entity")]
+    ApplicationReviews,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::application_documents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationDocuments.def()
+    }
+}
+
+impl Related<super::application_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationEvents.def()
+    }
+}
+
+impl Related<super::application_reviews::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationReviews.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
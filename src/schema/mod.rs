@@ -1,10 +1,31 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
+pub mod ai_daily_requests;
+pub mod ai_quotas;
+pub mod ai_usage;
+pub mod application_documents;
+pub mod application_drafts;
+pub mod application_events;
+pub mod application_forms;
+pub mod application_reviews;
 pub mod applications;
+pub mod citizens;
+pub mod citizenship_credentials;
+pub mod course_certificates;
+pub mod course_prerequisites;
+pub mod course_units;
+pub mod courses;
+pub mod email_verification_tokens;
 pub mod oauth_apps;
 pub mod oauth_grants;
 pub mod password_reset_tokens;
+pub mod question_assessment_overrides;
 pub mod question_assessments;
+pub mod question_attempts;
 pub mod sea_orm_active_enums;
+pub mod tutor_messages;
+pub mod tutor_threads;
+pub mod unit_prerequisites;
 pub mod unit_progress;
+pub mod unit_questions;
 pub mod users;
@@ -1,6 +1,7 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
 use super::sea_orm_active_enums::Assessment;
+use crate::assessments::rubric::RubricScores;
 use async_graphql::SimpleObject;
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
@@ -23,6 +24,21 @@ pub struct Model {
     pub answer: String,
     pub assessment: Assessment,
     pub feedback: String,
+    /// How the answer scored on each criterion, if the question has a rubric
+    pub rubric_scores: Option<RubricScores>,
+    /// Which of the user's attempts this is, starting from 1
+    pub attempt: i32,
+    /// When the learner asked for a teacher to review the assessment
+    pub flagged_at: Option<DateTime<Utc>>,
+    pub flag_reason: Option<String>,
+    #[graphql(skip)]
+    pub reviewed_by: Option<Uuid>,
+    /// When a teacher reviewed the assessment, if one has. The assessment
+    /// and feedback are then the teacher's rather than the grader's.
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// The version of the prompt the answer was graded with, unless it
+    /// wasn't sent to a model
+    pub prompt_version: Option<i32>,
     pub updated_at: DateTime<Utc>,
 }
 
@@ -40,6 +40,8 @@ pub struct Model {
     pub referrer: Option<Uuid>,
     #[graphql(skip)]
     pub stripe_customer_id: Option<String>,
+    #[graphql(guard = "ScopeGuard::new(\"profile:read:email\")")]
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -50,6 +52,16 @@ pub enum Relation {
     UnitProgress,
     #[sea_orm(has_many = "super::password_reset_tokens::Entity")]
     PasswordResetTokens,
+    #[sea_orm(has_many = "super::application_drafts::Entity")]
+    ApplicationDrafts,
+    #[sea_orm(has_many = "super::applications::Entity")]
+    Applications,
+    #[sea_orm(has_many = "super::email_verification_tokens::Entity")]
+    EmailVerificationTokens,
+    #[sea_orm(has_one = "super::citizens::Entity")]
+    Citizens,
+    #[sea_orm(has_many = "super::citizenship_credentials::Entity")]
+    CitizenshipCredentials,
 }
 
 impl Related<super::question_assessments::Entity> for Entity {
@@ -69,4 +81,35 @@ impl Related<super::password_reset_tokens::Entity> for Entity {
         Relation::PasswordResetTokens.def()
     }
 }
+
+impl Related<super::application_drafts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationDrafts.def()
+    }
+}
+
+impl Related<super::applications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Applications.def()
+    }
+}
+
+impl Related<super::email_verification_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EmailVerificationTokens.def()
+    }
+}
+
+impl Related<super::citizens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Citizens.def()
+    }
+}
+
+impl Related<super::citizenship_credentials::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CitizenshipCredentials.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,56 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use async_graphql::async_trait::async_trait;
+
+use super::DocumentStorage;
+
+/// Stores files in a directory, for local development and tests
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        // keys are generated by us, but never let one escape the root directory
+        if !Path::new(key)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            anyhow::bail!("Invalid storage key: {}", key);
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl DocumentStorage for LocalStorage {
+    async fn put(&self, key: &str, _content_type: &str, content: &[u8]) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        Ok(tokio::fs::write(path, content).await?)
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(tokio::fs::read(self.path(key)?).await?)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        Ok(tokio::fs::remove_file(self.path(key)?).await?)
+    }
+
+    fn download_url(&self, _key: &str, _expires_in: Duration) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,44 @@
+//! Storage for uploaded files, such as the documents attached to applications
+
+mod local_storage;
+mod s3_storage;
+
+use std::{sync::Arc, time::Duration};
+
+use async_graphql::async_trait::async_trait;
+
+pub use local_storage::LocalStorage;
+pub use s3_storage::S3Storage;
+
+use crate::util::variables::SECRET_VARIABLES;
+
+#[async_trait]
+pub trait DocumentStorage: Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, content: &[u8]) -> anyhow::Result<()>;
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+
+    /// A URL the file can be downloaded from until `expires_in` has passed,
+    /// or `None` if the backend can't serve files itself
+    fn download_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<Option<String>>;
+}
+
+/// Uses S3 if a bucket is configured, and the local filesystem otherwise.
+/// Production must use S3: Lambda's filesystem is temporary and not shared
+/// between instances, so documents stored on it would be lost.
+pub fn storage_from_env() -> anyhow::Result<Arc<dyn DocumentStorage>> {
+    if SECRET_VARIABLES.production && SECRET_VARIABLES.s3_bucket.is_none() {
+        anyhow::bail!("S3_BUCKET must be set in production");
+    }
+
+    Ok(match &SECRET_VARIABLES.s3_bucket {
+        Some(bucket) => Arc::new(S3Storage::new(
+            bucket,
+            &SECRET_VARIABLES.s3_region,
+            SECRET_VARIABLES.s3_endpoint.as_deref(),
+        )?),
+        None => Arc::new(LocalStorage::new(&SECRET_VARIABLES.document_storage_path)),
+    })
+}
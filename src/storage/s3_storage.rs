@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use async_graphql::async_trait::async_trait;
+use s3::{creds::Credentials, Bucket, Region};
+
+use super::DocumentStorage;
+
+/// Stores files in an S3 bucket, or any S3-compatible service if an endpoint is given
+pub struct S3Storage {
+    bucket: Bucket,
+}
+
+impl S3Storage {
+    /// Credentials are read from the standard AWS environment variables and profiles
+    pub fn new(bucket: &str, region: &str, endpoint: Option<&str>) -> anyhow::Result<Self> {
+        let credentials = Credentials::default()?;
+
+        let bucket = match endpoint {
+            // most S3-compatible services only support path style requests
+            Some(endpoint) => Bucket::new(
+                bucket,
+                Region::Custom {
+                    region: region.to_string(),
+                    endpoint: endpoint.to_string(),
+                },
+                credentials,
+            )?
+            .with_path_style(),
+            None => Bucket::new(bucket, region.parse()?, credentials)?,
+        };
+
+        Ok(Self { bucket })
+    }
+}
+
+#[async_trait]
+impl DocumentStorage for S3Storage {
+    async fn put(&self, key: &str, content_type: &str, content: &[u8]) -> anyhow::Result<()> {
+        self.bucket
+            .put_object_with_content_type(key, content, content_type)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(self.bucket.get_object(key).await?.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.bucket.delete_object(key).await?;
+
+        Ok(())
+    }
+
+    fn download_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<Option<String>> {
+        Ok(Some(self.bucket.presign_get(
+            key,
+            expires_in.as_secs() as u32,
+            None,
+        )?))
+    }
+}
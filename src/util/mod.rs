@@ -1,4 +1,5 @@
 pub mod crack_seconds;
 pub mod jsonb;
+pub mod phone;
 pub mod stripe;
 pub mod variables;
@@ -0,0 +1,458 @@
+use crate::error::new_err;
+
+/// ISO 3166-1 alpha-2 country codes and their ITU-T E.164 country calling codes.
+///
+/// Countries in the North American Numbering Plan all share calling code `1`,
+/// and their area codes are treated as part of the national number.
+pub const COUNTRY_CALLING_CODES: &[(&str, &str)] = &[
+    ("AD", "376"),
+    ("AE", "971"),
+    ("AF", "93"),
+    ("AG", "1"),
+    ("AI", "1"),
+    ("AL", "355"),
+    ("AM", "374"),
+    ("AO", "244"),
+    ("AQ", "672"),
+    ("AR", "54"),
+    ("AS", "1"),
+    ("AT", "43"),
+    ("AU", "61"),
+    ("AW", "297"),
+    ("AX", "358"),
+    ("AZ", "994"),
+    ("BA", "387"),
+    ("BB", "1"),
+    ("BD", "880"),
+    ("BE", "32"),
+    ("BF", "226"),
+    ("BG", "359"),
+    ("BH", "973"),
+    ("BI", "257"),
+    ("BJ", "229"),
+    ("BL", "590"),
+    ("BM", "1"),
+    ("BN", "673"),
+    ("BO", "591"),
+    ("BQ", "599"),
+    ("BR", "55"),
+    ("BS", "1"),
+    ("BT", "975"),
+    ("BW", "267"),
+    ("BY", "375"),
+    ("BZ", "501"),
+    ("CA", "1"),
+    ("CC", "61"),
+    ("CD", "243"),
+    ("CF", "236"),
+    ("CG", "242"),
+    ("CH", "41"),
+    ("CI", "225"),
+    ("CK", "682"),
+    ("CL", "56"),
+    ("CM", "237"),
+    ("CN", "86"),
+    ("CO", "57"),
+    ("CR", "506"),
+    ("CU", "53"),
+    ("CV", "238"),
+    ("CW", "599"),
+    ("CX", "61"),
+    ("CY", "357"),
+    ("CZ", "420"),
+    ("DE", "49"),
+    ("DJ", "253"),
+    ("DK", "45"),
+    ("DM", "1"),
+    ("DO", "1"),
+    ("DZ", "213"),
+    ("EC", "593"),
+    ("EE", "372"),
+    ("EG", "20"),
+    ("EH", "212"),
+    ("ER", "291"),
+    ("ES", "34"),
+    ("ET", "251"),
+    ("FI", "358"),
+    ("FJ", "679"),
+    ("FK", "500"),
+    ("FM", "691"),
+    ("FO", "298"),
+    ("FR", "33"),
+    ("GA", "241"),
+    ("GB", "44"),
+    ("GD", "1"),
+    ("GE", "995"),
+    ("GF", "594"),
+    ("GG", "44"),
+    ("GH", "233"),
+    ("GI", "350"),
+    ("GL", "299"),
+    ("GM", "220"),
+    ("GN", "224"),
+    ("GP", "590"),
+    ("GQ", "240"),
+    ("GR", "30"),
+    ("GT", "502"),
+    ("GU", "1"),
+    ("GW", "245"),
+    ("GY", "592"),
+    ("HK", "852"),
+    ("HN", "504"),
+    ("HR", "385"),
+    ("HT", "509"),
+    ("HU", "36"),
+    ("ID", "62"),
+    ("IE", "353"),
+    ("IL", "972"),
+    ("IM", "44"),
+    ("IN", "91"),
+    ("IO", "246"),
+    ("IQ", "964"),
+    ("IR", "98"),
+    ("IS", "354"),
+    ("IT", "39"),
+    ("JE", "44"),
+    ("JM", "1"),
+    ("JO", "962"),
+    ("JP", "81"),
+    ("KE", "254"),
+    ("KG", "996"),
+    ("KH", "855"),
+    ("KI", "686"),
+    ("KM", "269"),
+    ("KN", "1"),
+    ("KP", "850"),
+    ("KR", "82"),
+    ("KW", "965"),
+    ("KY", "1"),
+    ("KZ", "7"),
+    ("LA", "856"),
+    ("LB", "961"),
+    ("LC", "1"),
+    ("LI", "423"),
+    ("LK", "94"),
+    ("LR", "231"),
+    ("LS", "266"),
+    ("LT", "370"),
+    ("LU", "352"),
+    ("LV", "371"),
+    ("LY", "218"),
+    ("MA", "212"),
+    ("MC", "377"),
+    ("MD", "373"),
+    ("ME", "382"),
+    ("MF", "590"),
+    ("MG", "261"),
+    ("MH", "692"),
+    ("MK", "389"),
+    ("ML", "223"),
+    ("MM", "95"),
+    ("MN", "976"),
+    ("MO", "853"),
+    ("MP", "1"),
+    ("MQ", "596"),
+    ("MR", "222"),
+    ("MS", "1"),
+    ("MT", "356"),
+    ("MU", "230"),
+    ("MV", "960"),
+    ("MW", "265"),
+    ("MX", "52"),
+    ("MY", "60"),
+    ("MZ", "258"),
+    ("NA", "264"),
+    ("NC", "687"),
+    ("NE", "227"),
+    ("NF", "672"),
+    ("NG", "234"),
+    ("NI", "505"),
+    ("NL", "31"),
+    ("NO", "47"),
+    ("NP", "977"),
+    ("NR", "674"),
+    ("NU", "683"),
+    ("NZ", "64"),
+    ("OM", "968"),
+    ("PA", "507"),
+    ("PE", "51"),
+    ("PF", "689"),
+    ("PG", "675"),
+    ("PH", "63"),
+    ("PK", "92"),
+    ("PL", "48"),
+    ("PM", "508"),
+    ("PN", "64"),
+    ("PR", "1"),
+    ("PS", "970"),
+    ("PT", "351"),
+    ("PW", "680"),
+    ("PY", "595"),
+    ("QA", "974"),
+    ("RE", "262"),
+    ("RO", "40"),
+    ("RS", "381"),
+    ("RU", "7"),
+    ("RW", "250"),
+    ("SA", "966"),
+    ("SB", "677"),
+    ("SC", "248"),
+    ("SD", "249"),
+    ("SE", "46"),
+    ("SG", "65"),
+    ("SH", "290"),
+    ("SI", "386"),
+    ("SJ", "47"),
+    ("SK", "421"),
+    ("SL", "232"),
+    ("SM", "378"),
+    ("SN", "221"),
+    ("SO", "252"),
+    ("SR", "597"),
+    ("SS", "211"),
+    ("ST", "239"),
+    ("SV", "503"),
+    ("SX", "1"),
+    ("SY", "963"),
+    ("SZ", "268"),
+    ("TC", "1"),
+    ("TD", "235"),
+    ("TG", "228"),
+    ("TH", "66"),
+    ("TJ", "992"),
+    ("TK", "690"),
+    ("TL", "670"),
+    ("TM", "993"),
+    ("TN", "216"),
+    ("TO", "676"),
+    ("TR", "90"),
+    ("TT", "1"),
+    ("TV", "688"),
+    ("TW", "886"),
+    ("TZ", "255"),
+    ("UA", "380"),
+    ("UG", "256"),
+    ("US", "1"),
+    ("UY", "598"),
+    ("UZ", "998"),
+    ("VA", "39"),
+    ("VC", "1"),
+    ("VE", "58"),
+    ("VG", "1"),
+    ("VI", "1"),
+    ("VN", "84"),
+    ("VU", "678"),
+    ("WF", "681"),
+    ("WS", "685"),
+    ("XK", "383"),
+    ("YE", "967"),
+    ("YT", "262"),
+    ("ZA", "27"),
+    ("ZM", "260"),
+    ("ZW", "263"),
+];
+
+/// E.164 numbers have at most 15 digits, including the calling code
+const E164_MAX_DIGITS: usize = 15;
+/// The shortest national numbers in use are 4 digits long (e.g. Niue)
+const NATIONAL_MIN_DIGITS: usize = 4;
+
+/// Countries whose national numbers aren't dialled with a trunk prefix. In
+/// Italy, San Marino and the Vatican the leading `0` is part of the number
+/// and is kept in international format.
+const NO_TRUNK_PREFIX: &[&str] = &[
+    "BH", "CY", "CZ", "DK", "EE", "ES", "GR", "HK", "IS", "IT", "KW", "LU", "LV", "MO", "MT", "NO",
+    "OM", "PL", "PT", "QA", "SG", "SM", "VA",
+];
+
+/// The prefix dialled before national numbers within the country, if any
+fn trunk_prefix(country_code: &str, calling_code: &str) -> Option<&'static str> {
+    match calling_code {
+        _ if NO_TRUNK_PREFIX.contains(&country_code) => None,
+        // the North American Numbering Plan
+        "1" => Some("1"),
+        // Russia and Kazakhstan
+        "7" => Some("8"),
+        _ => Some("0"),
+    }
+}
+
+/// A phone number that has been validated against the country tables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoneNumber {
+    /// ISO 3166-1 alpha-2 country code, e.g. `AU`
+    pub country_code: String,
+    /// ITU calling code without the leading `+`, e.g. `61`
+    pub calling_code: String,
+    /// The full number in E.164 format, e.g. `+61412345678`
+    pub e164: String,
+}
+
+/// Returns the calling code for an ISO 3166-1 alpha-2 country code
+pub fn calling_code_for_country(country_code: &str) -> Option<&'static str> {
+    COUNTRY_CALLING_CODES
+        .binary_search_by(|(code, _)| (*code).cmp(country_code))
+        .ok()
+        .map(|index| COUNTRY_CALLING_CODES[index].1)
+}
+
+/// Validates the country code, calling code and phone number a user
+/// provided, and normalises the phone number to E.164.
+///
+/// The phone number may be given either in national format (with or without
+/// the country's trunk prefix, e.g. `0` in Australia) or in international
+/// format starting with `+` or `00`.
+pub fn normalise_phone_number(
+    calling_code: &str,
+    country_code: &str,
+    phone_number: &str,
+) -> async_graphql::Result<PhoneNumber> {
+    let country_code = country_code.trim().to_uppercase();
+    let expected_calling_code = calling_code_for_country(&country_code).ok_or_else(|| {
+        new_err(
+            "INVALID_COUNTRY_CODE",
+            &format!("Unknown ISO 3166 country code: {}", country_code),
+        )
+    })?;
+
+    let calling_code = calling_code.trim().trim_start_matches('+');
+    if calling_code != expected_calling_code {
+        return Err(new_err(
+            "INVALID_CALLING_CODE",
+            &format!(
+                "Calling code +{} does not match country {} (+{})",
+                calling_code, country_code, expected_calling_code
+            ),
+        ));
+    }
+
+    let phone_number = phone_number.trim();
+
+    let mut digits = String::with_capacity(phone_number.len());
+    for (i, c) in phone_number.chars().enumerate() {
+        match c {
+            '0'..='9' => digits.push(c),
+            '+' if i == 0 => {}
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => {
+                return Err(new_err(
+                    "INVALID_PHONE_NUMBER",
+                    "Phone number may only contain digits, spaces, dashes, dots and brackets",
+                ))
+            }
+        }
+    }
+
+    let international = match phone_number.starts_with('+') {
+        true => Some(digits.as_str()),
+        false => digits.strip_prefix("00"),
+    };
+
+    let national_number = match international {
+        Some(digits) => digits.strip_prefix(expected_calling_code).ok_or_else(|| {
+            new_err(
+                "INVALID_PHONE_NUMBER",
+                &format!(
+                    "Phone number does not start with the calling code +{}",
+                    expected_calling_code
+                ),
+            )
+        })?,
+        // strip the trunk prefix, e.g. 0412 345 678 in Australia
+        None => match trunk_prefix(&country_code, expected_calling_code) {
+            Some(prefix) => digits.strip_prefix(prefix).unwrap_or(&digits),
+            None => &digits,
+        },
+    };
+
+    if national_number.len() < NATIONAL_MIN_DIGITS
+        || expected_calling_code.len() + national_number.len() > E164_MAX_DIGITS
+    {
+        return Err(new_err(
+            "INVALID_PHONE_NUMBER",
+            "Phone number has an invalid number of digits",
+        ));
+    }
+
+    Ok(PhoneNumber {
+        e164: format!("+{}{}", expected_calling_code, national_number),
+        calling_code: expected_calling_code.to_string(),
+        country_code,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn country_table_is_sorted() {
+        assert!(COUNTRY_CALLING_CODES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn normalises_national_number() {
+        let phone = normalise_phone_number("61", "au", "0412 345 678").unwrap();
+
+        assert_eq!(phone.country_code, "AU");
+        assert_eq!(phone.calling_code, "61");
+        assert_eq!(phone.e164, "+61412345678");
+    }
+
+    #[test]
+    fn normalises_international_number() {
+        let phone = normalise_phone_number("+1", "US", "+1 (555) 555-5555").unwrap();
+
+        assert_eq!(phone.e164, "+15555555555");
+    }
+
+    #[test]
+    fn normalises_international_number_with_00_prefix() {
+        let phone = normalise_phone_number("61", "AU", "0061 412 345 678").unwrap();
+
+        assert_eq!(phone.e164, "+61412345678");
+        assert!(normalise_phone_number("61", "AU", "0044 412 345 678").is_err());
+    }
+
+    #[test]
+    fn strips_only_the_trunk_prefix() {
+        // the leading 0 is part of Italian numbers
+        let phone = normalise_phone_number("39", "IT", "06 1234 5678").unwrap();
+        assert_eq!(phone.e164, "+390612345678");
+        let phone = normalise_phone_number("39", "IT", "+39 06 1234 5678").unwrap();
+        assert_eq!(phone.e164, "+390612345678");
+
+        let phone = normalise_phone_number("1", "US", "1 555 555 5555").unwrap();
+        assert_eq!(phone.e164, "+15555555555");
+        let phone = normalise_phone_number("7", "RU", "8 912 345 6789").unwrap();
+        assert_eq!(phone.e164, "+79123456789");
+    }
+
+    #[test]
+    fn fails_for_swapped_codes() {
+        let err = normalise_phone_number("AU", "61", "0412345678").unwrap_err();
+
+        assert_eq!(err.message, "Unknown ISO 3166 country code: 61");
+    }
+
+    #[test]
+    fn fails_for_mismatched_calling_code() {
+        assert!(normalise_phone_number("44", "AU", "0412345678").is_err());
+    }
+
+    #[test]
+    fn fails_for_wrong_international_prefix() {
+        assert!(normalise_phone_number("61", "AU", "+44 412 345 678").is_err());
+    }
+
+    #[test]
+    fn fails_for_invalid_length() {
+        assert!(normalise_phone_number("61", "AU", "000").is_err());
+        assert!(normalise_phone_number("61", "AU", "1234567890123456").is_err());
+    }
+
+    #[test]
+    fn fails_for_invalid_characters() {
+        assert!(normalise_phone_number("61", "AU", "0412 abc 678").is_err());
+    }
+}
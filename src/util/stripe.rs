@@ -1,5 +1,32 @@
+use std::str::FromStr;
+
+use stripe::PriceId;
+
 use super::variables::SECRET_VARIABLES;
 
 pub fn get_stripe_client() -> stripe::Client {
     stripe::Client::new(&SECRET_VARIABLES.stripe_secret_key)
 }
+
+/// The customer's Light University subscription, unless they don't have one
+/// or it has been cancelled
+pub async fn light_university_subscription(
+    stripe_customer_id: &str,
+) -> async_graphql::Result<Option<stripe::Subscription>> {
+    let client = get_stripe_client();
+
+    Ok(stripe::Subscription::list(
+        &client,
+        &stripe::ListSubscriptions {
+            customer: Some(stripe::CustomerId::from_str(stripe_customer_id)?),
+            price: Some(PriceId::from_str(
+                &SECRET_VARIABLES.light_university_product_id,
+            )?),
+            ..Default::default()
+        },
+    )
+    .await?
+    .data
+    .get(0)
+    .cloned())
+}
@@ -1,20 +1,62 @@
 use lazy_static::lazy_static;
 use openai::set_key;
 
+use crate::citizens::credentials::CredentialKey;
+
 pub struct SecretVariables {
+    /// Whether this is the production deployment, set by `PRODUCTION`
+    pub production: bool,
+    pub openai_key: String,
     pub jwt_secret: Vec<u8>,
+    /// Signs citizenship credentials, so they can be verified with the public key
+    pub credential_key: CredentialKey,
     pub sendgrid_api_key: String,
     pub light_university_product_id: String,
     pub stripe_secret_key: String,
     pub database_url: Option<String>,
     pub app_secret: String,
+    /// ISO 3166 codes of countries whose residents and citizens can't apply for citizenship
+    pub citizenship_restricted_countries: Vec<String>,
+    /// Uploaded documents are stored in this S3 bucket if it is set,
+    /// otherwise they are stored in `document_storage_path`
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    /// Endpoint of an S3-compatible service, if not using AWS
+    pub s3_endpoint: Option<String>,
+    pub document_storage_path: String,
+    /// Which `AssessmentProvider` grades answers: `openai`, `http` or `mock`
+    pub assessment_provider: String,
+    pub assessment_model: String,
+    /// Base URL of the OpenAI-compatible API used by the `http` provider
+    pub assessment_api_url: Option<String>,
+    pub assessment_api_key: Option<String>,
+    /// What the assessment model costs, in US dollars per million tokens
+    pub assessment_prompt_price: f64,
+    pub assessment_completion_price: f64,
+    /// How many AI requests users can make a day, unless they have their own quota
+    pub ai_daily_quota: i32,
+    /// How many AI requests users with a Light University subscription can make a day
+    pub ai_subscriber_daily_quota: i32,
+    /// How long assessments are reused for identical answers, 0 to always grade again
+    pub assessment_cache_hours: i64,
+}
+
+fn number_var<T: std::str::FromStr>(name: &str, default: T) -> T {
+    match dotenv::var(name) {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            tracing::warn!("{} must be a number, using the default instead", name);
+            default
+        }),
+        Err(_) => default,
+    }
 }
 
 lazy_static! {
     pub static ref SECRET_VARIABLES: SecretVariables = {
         dotenv::dotenv().ok();
 
-        set_key(dotenv::var("OPENAI_KEY").expect("OPENAI_KEY not set in .env"));
+        let openai_key = dotenv::var("OPENAI_KEY").expect("OPENAI_KEY not set in .env");
+        set_key(openai_key.clone());
 
         let in_prod = dotenv::var("PRODUCTION")
             .ok()
@@ -22,9 +64,16 @@ lazy_static! {
             == "true";
 
         SecretVariables {
+            production: in_prod,
+            openai_key,
             jwt_secret: dotenv::var("JWT_SECRET")
                 .expect("JWT_SECRET is not set in env variables")
                 .into_bytes(),
+            credential_key: CredentialKey::from_pem(
+                &dotenv::var("CREDENTIAL_SIGNING_KEY")
+                    .expect("CREDENTIAL_SIGNING_KEY is not set in env variables"),
+            )
+            .expect("CREDENTIAL_SIGNING_KEY must be an Ed25519 private key in PEM format"),
             sendgrid_api_key: dotenv::var("SENDGRID_KEY")
                 .expect("SENDGRID_KEY is not set in env variables"),
             light_university_product_id: match in_prod {
@@ -37,6 +86,40 @@ lazy_static! {
             database_url: dotenv::var("DATABASE_URL").ok(),
             app_secret: dotenv::var("LUMINA_APP_SECRET")
                 .expect("LUMINA_APP_SECRET is not set in env variables"),
+            citizenship_restricted_countries: dotenv::var("CITIZENSHIP_RESTRICTED_COUNTRIES")
+                .unwrap_or_default()
+                .split(',')
+                .map(|country| country.trim().to_uppercase())
+                .filter(|country| !country.is_empty())
+                .collect(),
+            // the deploy workflow sets these to empty strings when their
+            // secrets aren't configured
+            s3_bucket: dotenv::var("S3_BUCKET").ok().filter(|bucket| !bucket.is_empty()),
+            s3_region: dotenv::var("S3_REGION")
+                .ok()
+                .filter(|region| !region.is_empty())
+                .unwrap_or(String::from("ap-southeast-1")),
+            s3_endpoint: dotenv::var("S3_ENDPOINT")
+                .ok()
+                .filter(|endpoint| !endpoint.is_empty()),
+            document_storage_path: dotenv::var("DOCUMENT_STORAGE_PATH").unwrap_or_else(|_| {
+                std::env::temp_dir()
+                    .join("lumina-documents")
+                    .to_string_lossy()
+                    .into_owned()
+            }),
+            assessment_provider: dotenv::var("ASSESSMENT_PROVIDER")
+                .unwrap_or_else(|_| String::from("openai")),
+            assessment_model: dotenv::var("ASSESSMENT_MODEL")
+                .unwrap_or_else(|_| String::from("gpt-4-0613")),
+            assessment_api_url: dotenv::var("ASSESSMENT_API_URL").ok(),
+            assessment_api_key: dotenv::var("ASSESSMENT_API_KEY").ok(),
+            // GPT-4 prices
+            assessment_prompt_price: number_var("ASSESSMENT_PROMPT_PRICE", 30.0),
+            assessment_completion_price: number_var("ASSESSMENT_COMPLETION_PRICE", 60.0),
+            ai_daily_quota: number_var("AI_DAILY_QUOTA", 20),
+            ai_subscriber_daily_quota: number_var("AI_SUBSCRIBER_DAILY_QUOTA", 200),
+            assessment_cache_hours: number_var("ASSESSMENT_CACHE_HOURS", 168),
         }
     };
 }
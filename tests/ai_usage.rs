@@ -0,0 +1,178 @@
+use serde_json::json;
+use shared::SharedApp;
+
+mod shared;
+
+async fn assess(answer: &str, token: &Option<String>, shared_app: &SharedApp) -> serde_json::Value {
+    shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            question_assessment(course_slug: "test-course", unit_slug: "test-unit", question_slug: "test-question", answer: "{}") {{
+                user_id
+            }}
+        }}
+    "#,
+                answer
+            ),
+            token,
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn ai_requests_are_limited_and_costed() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app
+        .create_course("test-course", &["test-unit"], &["test-question"])
+        .await?;
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let ai_usage = r#"
+        query {
+            me {
+                ai_usage {
+                    tier
+                    daily_quota
+                    requests_today
+                    remaining_today
+                    prompt_tokens_today
+                    completion_tokens_today
+                }
+            }
+        }
+    "#;
+    let response = shared_app.query(ai_usage, &token).await?;
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(response["data"]["me"]["ai_usage"]["tier"], "FREE");
+    assert_eq!(response["data"]["me"]["ai_usage"]["requests_today"], 0);
+
+    let response = assess("2", &token, &shared_app).await;
+    assert_eq!(response["errors"], json!(null));
+    let user_id = response["data"]["question_assessment"]["user_id"].clone();
+
+    let admin_email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&admin_email, "admin").await?;
+    let admin_token = shared_app.login_specific(&admin_email).await?;
+
+    let set_quota = r#"
+        mutation($user_id: UUID!) {
+            set_ai_quota(user_id: $user_id, daily_requests: 2)
+        }
+    "#;
+    let response = shared_app
+        .query_with_variables(set_quota, json!({ "user_id": user_id }), &token)
+        .await?;
+    assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+    let response = shared_app
+        .query_with_variables(set_quota, json!({ "user_id": user_id }), &admin_token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(response["data"]["set_ai_quota"], 2);
+
+    let response = assess("one plus one", &token, &shared_app).await;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = assess("two", &token, &shared_app).await;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "AI_QUOTA_EXCEEDED"
+    );
+
+    // the mock provider counts the question and answer's words as tokens
+    let response = shared_app.query(ai_usage, &token).await?;
+    assert_eq!(
+        response["data"]["me"]["ai_usage"],
+        json!({
+            "tier": "CUSTOM",
+            "daily_quota": 2,
+            "requests_today": 2,
+            "remaining_today": 0,
+            "prompt_tokens_today": 10,
+            "completion_tokens_today": 20,
+        })
+    );
+
+    let report = r#"
+        query($since: DateTime!) {
+            ai_cost_report(since: $since) {
+                requests
+                prompt_tokens
+                cost_usd
+                users {
+                    email
+                    requests
+                }
+            }
+        }
+    "#;
+    let since = json!({ "since": "2000-01-01T00:00:00Z" });
+    let response = shared_app
+        .query_with_variables(report, since.clone(), &token)
+        .await?;
+    assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+    let response = shared_app
+        .query_with_variables(report, since, &admin_token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(response["data"]["ai_cost_report"]["requests"], 2);
+    assert_eq!(response["data"]["ai_cost_report"]["prompt_tokens"], 10);
+    assert!(response["data"]["ai_cost_report"]["cost_usd"].as_f64() > Some(0.0));
+    assert_eq!(
+        response["data"]["ai_cost_report"]["users"],
+        json!([{ "email": email, "requests": 2 }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn concurrent_requests_cannot_exceed_the_quota() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app
+        .create_course("test-course", &["test-unit"], &["test-question"])
+        .await?;
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+    let response = shared_app.query(r#"query { me { id } }"#, &token).await?;
+    let user_id = response["data"]["me"]["id"].clone();
+
+    let admin_email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&admin_email, "admin").await?;
+    let admin_token = shared_app.login_specific(&admin_email).await?;
+    let response = shared_app
+        .query_with_variables(
+            r#"
+        mutation($user_id: UUID!) {
+            set_ai_quota(user_id: $user_id, daily_requests: 1)
+        }
+    "#,
+            json!({ "user_id": user_id }),
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let (first, second) = tokio::join!(
+        assess("one plus one", &token, &shared_app),
+        assess("the number two", &token, &shared_app),
+    );
+    let mut codes = [first, second]
+        .iter()
+        .map(|response| response["errors"][0]["extensions"]["code"].clone())
+        .collect::<Vec<_>>();
+    codes.sort_by_key(|code| code.is_null());
+    assert_eq!(codes, [json!("AI_QUOTA_EXCEEDED"), json!(null)]);
+
+    Ok(())
+}
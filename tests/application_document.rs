@@ -0,0 +1,156 @@
+use serde_json::json;
+use shared::SharedApp;
+
+mod shared;
+
+const PDF: &[u8] = b"%PDF-1.7\n%test document\n";
+
+const UPLOAD_DOCUMENT: &str = r#"
+    mutation ($application_id: UUID!, $file: Upload!) {
+        upload_application_document(application_id: $application_id, file: $file) {
+            id
+            file_name
+            content_type
+            size
+        }
+    }
+"#;
+
+async fn login_as_applicant(shared_app: &SharedApp) -> Result<Option<String>, anyhow::Error> {
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+
+    shared_app.login_specific(&email).await
+}
+
+#[tokio::test]
+async fn applicant_can_upload_document() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let token = login_as_applicant(&shared_app).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let response = shared_app
+        .upload(
+            UPLOAD_DOCUMENT,
+            json!({ "application_id": application_id }),
+            "passport.pdf",
+            "application/pdf",
+            PDF,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["upload_application_document"]["file_name"],
+        json!("passport.pdf")
+    );
+    assert_eq!(
+        response["data"]["upload_application_document"]["size"],
+        json!(PDF.len())
+    );
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                applications {
+                    documents {
+                        file_name
+                        content_type
+                        content
+                    }
+                }
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let document = &response["data"]["me"]["applications"][0]["documents"][0];
+    assert_eq!(document["content_type"], json!("application/pdf"));
+    assert_eq!(
+        document["content"],
+        json!("JVBERi0xLjcKJXRlc3QgZG9jdW1lbnQK")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_mismatched_document_type() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let token = login_as_applicant(&shared_app).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let response = shared_app
+        .upload(
+            UPLOAD_DOCUMENT,
+            json!({ "application_id": application_id }),
+            "passport.png",
+            "image/png",
+            PDF,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_DOCUMENT")
+    );
+
+    let response = shared_app
+        .upload(
+            UPLOAD_DOCUMENT,
+            json!({ "application_id": application_id }),
+            "passport.html",
+            "text/html",
+            b"<html></html>",
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("UNSUPPORTED_DOCUMENT_TYPE")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_upload_to_another_users_application() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let token = login_as_applicant(&shared_app).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let other_email = shared_app
+        .create_user_with_email("other@example.com")
+        .await?;
+    let other_token = shared_app.login_specific(&other_email).await?;
+
+    let response = shared_app
+        .upload(
+            UPLOAD_DOCUMENT,
+            json!({ "application_id": application_id }),
+            "passport.pdf",
+            "application/pdf",
+            PDF,
+            &other_token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("APPLICATION_NOT_FOUND")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use serde_json::{json, Value};
+use shared::SharedApp;
+
+mod shared;
+
+const SAVE_DRAFT: &str = r#"
+    mutation ($draft_id: UUID, $draft: CitizenshipApplicationDraftInput!) {
+        save_application_draft(draft_id: $draft_id, draft: { citizenship: $draft }) {
+            id
+            draft {
+                ... on CitizenshipApplicationDraft {
+                    first_name
+                    last_name
+                    country_of_residence
+                }
+            }
+            field_errors {
+                field
+                message
+            }
+        }
+    }
+"#;
+
+async fn save_draft(
+    shared_app: &SharedApp,
+    token: &Option<String>,
+    draft_id: Option<&str>,
+    draft: Value,
+) -> Result<Value, anyhow::Error> {
+    let response = shared_app
+        .query_with_variables(
+            SAVE_DRAFT,
+            json!({ "draft_id": draft_id, "draft": draft }),
+            token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    Ok(response["data"]["save_application_draft"].clone())
+}
+
+async fn submit_draft(
+    shared_app: &SharedApp,
+    token: &Option<String>,
+    draft_id: &str,
+) -> Result<Value, anyhow::Error> {
+    shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            submit_application_draft(draft_id: "{}")
+        }}
+    "#,
+                draft_id
+            ),
+            token,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn can_fill_in_draft_over_several_saves() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let draft = save_draft(
+        &shared_app,
+        &token,
+        None,
+        json!({ "first_name": "John", "last_name": "Doe" }),
+    )
+    .await?;
+    let draft_id = draft["id"].as_str().unwrap().to_string();
+
+    assert_eq!(
+        draft["field_errors"][0],
+        json!({ "field": "date_of_birth", "message": "is required" })
+    );
+
+    let response = submit_draft(&shared_app, &token, &draft_id).await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_APPLICATION")
+    );
+    assert_eq!(
+        response["errors"][0]["extensions"]["field_errors"][0]["field"],
+        json!("date_of_birth")
+    );
+
+    let draft = save_draft(
+        &shared_app,
+        &token,
+        Some(&draft_id),
+        json!({
+            "date_of_birth": "1990-01-01T00:00:00Z",
+            "sex": "MALE",
+            "country_of_citizenship": ["AU"],
+            "country_of_birth": "AU",
+            "country_of_residence": "AU",
+        }),
+    )
+    .await?;
+
+    assert_eq!(draft["field_errors"], json!([]));
+    assert_eq!(draft["draft"]["first_name"], json!("John"));
+
+    let response = submit_draft(&shared_app, &token, &draft_id).await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                application_drafts {
+                    id
+                }
+                applications {
+                    application {
+                        ... on CitizenshipApplication {
+                            first_name
+                            country_of_residence
+                        }
+                    }
+                }
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(response["data"]["me"]["application_drafts"], json!([]));
+    assert_eq!(
+        response["data"]["me"]["applications"][0]["application"],
+        json!({ "first_name": "John", "country_of_residence": "AU" })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn null_clears_draft_fields() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let draft = save_draft(
+        &shared_app,
+        &token,
+        None,
+        json!({ "first_name": "John", "last_name": "Doe" }),
+    )
+    .await?;
+
+    let draft = save_draft(
+        &shared_app,
+        &token,
+        draft["id"].as_str(),
+        json!({ "first_name": null }),
+    )
+    .await?;
+
+    assert_eq!(draft["draft"]["first_name"], json!(null));
+    assert_eq!(draft["draft"]["last_name"], json!("Doe"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn expired_drafts_are_hidden() -> Result<(), anyhow::Error> {
+    use graph_api::schema::application_drafts;
+    use sea_orm::{sea_query::Expr, Database, EntityTrait};
+
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let draft = save_draft(&shared_app, &token, None, json!({ "first_name": "John" })).await?;
+
+    let db = Database::connect(&shared_app.get_db_url()).await?;
+    application_drafts::Entity::update_many()
+        .col_expr(
+            application_drafts::Column::ExpiresAt,
+            Expr::value(chrono::Utc::now() - chrono::Duration::days(1)),
+        )
+        .exec(&db)
+        .await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                application_drafts {
+                    id
+                }
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["data"]["me"]["application_drafts"], json!([]));
+
+    let response = submit_draft(&shared_app, &token, draft["id"].as_str().unwrap()).await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("DRAFT_NOT_FOUND")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use serde_json::{json, Value};
+use shared::SharedApp;
+
+mod shared;
+
+async fn login_as_reviewer(shared_app: &SharedApp) -> Result<Option<String>, anyhow::Error> {
+    let email = shared_app
+        .create_user_with_email("reviewer@lumina.earth")
+        .await?;
+    shared_app.set_role(&email, "reviewer").await?;
+
+    shared_app.login_specific(&email).await
+}
+
+async fn timeline_kinds(
+    shared_app: &SharedApp,
+    token: &Option<String>,
+) -> Result<Vec<Value>, anyhow::Error> {
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                applications {
+                    timeline {
+                        kind
+                        status
+                        message
+                        internal
+                    }
+                }
+            }
+        }
+    "#,
+            token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    Ok(response["data"]["me"]["applications"][0]["timeline"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default())
+}
+
+#[tokio::test]
+async fn applicant_sees_timeline_without_internal_comments() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let reviewer_token = login_as_reviewer(&shared_app).await?;
+
+    for mutation in [
+        r#"request_application_document(application_id: "{}", document: "A copy of your passport") { kind }"#,
+        r#"comment_on_application(application_id: "{}", comment: "Passport looks edited", internal: true) { kind }"#,
+        r#"comment_on_application(application_id: "{}", comment: "Thanks for your patience") { kind }"#,
+        r#"reject_citizenship_application(application_id: "{}", reason: "Invalid passport") { id }"#,
+    ] {
+        let response = shared_app
+            .query(
+                &format!(
+                    "mutation {{ {} }}",
+                    mutation.replace("{}", &application_id.to_string())
+                ),
+                &reviewer_token,
+            )
+            .await?;
+        assert_eq!(response["errors"], json!(null));
+    }
+
+    assert_eq!(
+        timeline_kinds(&shared_app, &token).await?,
+        vec![
+            json!({ "kind": "SUBMITTED", "status": null, "message": null, "internal": false }),
+            json!({ "kind": "DOCUMENT_REQUESTED", "status": null, "message": "A copy of your passport", "internal": false }),
+            json!({ "kind": "COMMENT_ADDED", "status": null, "message": "Thanks for your patience", "internal": false }),
+            json!({ "kind": "STATUS_CHANGED", "status": "REJECTED", "message": "Invalid passport", "internal": false }),
+        ]
+    );
+
+    // reviewers see internal comments
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            citizenship_applications(filter: { status: REJECTED }) {
+                timeline {
+                    message
+                    internal
+                }
+            }
+        }
+    "#,
+            &reviewer_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["citizenship_applications"][0]["timeline"][2],
+        json!({ "message": "Passport looks edited", "internal": true })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_reviewers_can_comment() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let mutation = format!(
+        r#"
+        mutation {{
+            comment_on_application(application_id: "{}", comment: "Looks good to me") {{
+                id
+            }}
+        }}
+    "#,
+        application_id
+    );
+
+    let response = shared_app.query(&mutation, &token).await?;
+    assert_ne!(response["errors"], json!(null));
+
+    let reviewer_token = login_as_reviewer(&shared_app).await?;
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            comment_on_application(application_id: "{}", comment: "  ") {{
+                id
+            }}
+        }}
+    "#,
+                application_id
+            ),
+            &reviewer_token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_MESSAGE")
+    );
+
+    assert_eq!(timeline_kinds(&shared_app, &token).await?.len(), 1);
+
+    Ok(())
+}
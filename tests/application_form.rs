@@ -0,0 +1,260 @@
+use serde_json::{json, Value};
+use shared::SharedApp;
+
+mod shared;
+
+const PUBLISH_FORM: &str = r#"
+    mutation($schema: JSON!) {
+        publish_application_form(
+            application_type: "business_registration",
+            title: "Business registration",
+            schema: $schema
+        ) {
+            application_type
+            version
+            built_in
+        }
+    }
+"#;
+
+const SUBMIT_FORM_APPLICATION: &str = r#"
+    mutation($data: JSON!) {
+        submit_application(application: {
+            form: { application_type: "business_registration", data: $data }
+        })
+    }
+"#;
+
+fn business_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["name", "structure"],
+        "properties": {
+            "name": { "type": "string", "minLength": 1 },
+            "structure": { "enum": ["sole_trader", "company"] },
+            "company_number": { "type": "string" }
+        },
+        "if": { "properties": { "structure": { "const": "company" } } },
+        "then": { "required": ["company_number"] }
+    })
+}
+
+async fn publish_business_form(shared_app: &SharedApp) -> Result<Value, anyhow::Error> {
+    let email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&email, "admin").await?;
+    let admin_token = shared_app.login_specific(&email).await?;
+
+    shared_app
+        .query_with_variables(
+            PUBLISH_FORM,
+            json!({ "schema": business_schema() }),
+            &admin_token,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn admin_can_publish_form_versions() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let response = publish_business_form(&shared_app).await?;
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["publish_application_form"],
+        json!({
+            "application_type": "business_registration",
+            "version": 1,
+            "built_in": false,
+        })
+    );
+
+    let admin_token = shared_app.login_specific("admin@lumina.earth").await?;
+    let response = shared_app
+        .query_with_variables(
+            PUBLISH_FORM,
+            json!({ "schema": business_schema() }),
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["data"]["publish_application_form"]["version"],
+        json!(2)
+    );
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            application_forms {
+                application_type
+                version
+                built_in
+            }
+        }
+    "#,
+            &None,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["application_forms"],
+        json!([
+            { "application_type": "citizenship", "version": 1, "built_in": true },
+            { "application_type": "business_registration", "version": 2, "built_in": false },
+        ])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_publish_invalid_forms() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+    let response = shared_app
+        .query_with_variables(PUBLISH_FORM, json!({ "schema": business_schema() }), &token)
+        .await?;
+    assert_ne!(response["errors"], json!(null));
+
+    let email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&email, "admin").await?;
+    let admin_token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query_with_variables(
+            PUBLISH_FORM,
+            json!({ "schema": { "type": "object", "required": "name" } }),
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_FORM_SCHEMA"
+    );
+
+    let response = shared_app
+        .query_with_variables(
+            r#"
+        mutation($schema: JSON!) {
+            publish_application_form(application_type: "citizenship", title: "Citizenship", schema: $schema) {
+                version
+            }
+        }
+    "#,
+            json!({ "schema": business_schema() }),
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_APPLICATION_TYPE"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_submit_form_application() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    publish_business_form(&shared_app).await?;
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query_with_variables(
+            SUBMIT_FORM_APPLICATION,
+            json!({ "data": { "name": "Acme", "structure": "company", "company_number": "123" } }),
+            &token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                applications {
+                    application {
+                        ... on FormApplication {
+                            application_type
+                            form_version
+                            data
+                        }
+                    }
+                }
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["me"]["applications"][0],
+        json!({
+            "application": {
+                "application_type": "business_registration",
+                "form_version": 1,
+                "data": { "name": "Acme", "structure": "company", "company_number": "123" },
+            },
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn form_applications_are_validated() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    publish_business_form(&shared_app).await?;
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    // companies must give their company number
+    let response = shared_app
+        .query_with_variables(
+            SUBMIT_FORM_APPLICATION,
+            json!({ "data": { "name": "Acme", "structure": "company" } }),
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_APPLICATION"
+    );
+    assert_eq!(
+        response["errors"][0]["extensions"]["field_errors"][0]["field"],
+        "company_number"
+    );
+
+    let response = shared_app
+        .query_with_variables(
+            r#"
+        mutation($data: JSON!) {
+            submit_application(application: { form: { application_type: "residency", data: $data } })
+        }
+    "#,
+            json!({ "data": {} }),
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "FORM_NOT_FOUND"
+    );
+
+    Ok(())
+}
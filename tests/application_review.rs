@@ -0,0 +1,175 @@
+use serde_json::json;
+use shared::SharedApp;
+
+mod shared;
+
+async fn login_as_reviewer(shared_app: &SharedApp) -> Result<Option<String>, anyhow::Error> {
+    let email = shared_app
+        .create_user_with_email("reviewer@lumina.earth")
+        .await?;
+    shared_app.set_role(&email, "reviewer").await?;
+
+    shared_app.login_specific(&email).await
+}
+
+#[tokio::test]
+async fn reviewer_can_approve_application() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let reviewer_token = login_as_reviewer(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            citizenship_applications(filter: { country_of_residence: "country" }) {
+                id
+            }
+        }
+    "#,
+            &reviewer_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["citizenship_applications"][0]["id"],
+        json!(application_id)
+    );
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            approve_citizenship_application(application_id: "{}") {{
+                application {{
+                    ... on CitizenshipApplication {{
+                        citizenship_status
+                    }}
+                }}
+                reviews {{
+                    status
+                    reviewer_id
+                }}
+            }}
+        }}
+    "#,
+                application_id
+            ),
+            &reviewer_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let application = &response["data"]["approve_citizenship_application"];
+    assert_eq!(
+        application["application"]["citizenship_status"],
+        json!("APPROVED")
+    );
+    assert_eq!(application["reviews"][0]["status"], json!("APPROVED"));
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                citizenship_status
+                citizen {
+                    citizenship_number
+                    status
+                    application_id
+                }
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["me"]["citizenship_status"],
+        json!("ACTIVE")
+    );
+
+    let citizen = &response["data"]["me"]["citizen"];
+    assert_eq!(citizen["citizenship_number"], json!("L000000018"));
+    assert_eq!(citizen["status"], json!("ACTIVE"));
+    assert_eq!(citizen["application_id"], json!(application_id));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn decisions_are_final() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let reviewer_token = login_as_reviewer(&shared_app).await?;
+
+    let reject = format!(
+        r#"
+        mutation {{
+            reject_citizenship_application(application_id: "{}", reason: "Incomplete") {{
+                id
+            }}
+        }}
+    "#,
+        application_id
+    );
+
+    let response = shared_app.query(&reject, &reviewer_token).await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app.query(&reject, &reviewer_token).await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_STATUS_TRANSITION")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn applicants_cannot_review() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+    let application_id = shared_app.create_citizenship_application(&token).await?;
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            approve_citizenship_application(application_id: "{}") {{
+                id
+            }}
+        }}
+    "#,
+                application_id
+            ),
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("FORBIDDEN")
+    );
+
+    Ok(())
+}
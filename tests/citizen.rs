@@ -0,0 +1,158 @@
+use serde_json::{json, Value};
+use shared::SharedApp;
+
+mod shared;
+
+async fn login_as_admin(shared_app: &SharedApp) -> Result<Option<String>, anyhow::Error> {
+    let email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&email, "admin").await?;
+
+    shared_app.login_specific(&email).await
+}
+
+async fn citizen_status(
+    shared_app: &SharedApp,
+    token: &Option<String>,
+) -> Result<Value, anyhow::Error> {
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                citizenship_status
+                citizen {
+                    status
+                    status_reason
+                }
+            }
+        }
+    "#,
+            token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    Ok(response["data"]["me"].clone())
+}
+
+#[tokio::test]
+async fn admin_can_suspend_and_reinstate_citizen() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (user_id, token) = shared_app.create_citizen().await?;
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            suspend_citizen(user_id: "{}", reason: "Under investigation") {{
+                status
+            }}
+        }}
+    "#,
+                user_id
+            ),
+            &admin_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["suspend_citizen"]["status"],
+        json!("SUSPENDED")
+    );
+
+    let me = citizen_status(&shared_app, &token).await?;
+    assert_eq!(me["citizenship_status"], json!("SUSPENDED"));
+    assert_eq!(me["citizen"]["status_reason"], json!("Under investigation"));
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            reinstate_citizen(user_id: "{}") {{
+                status
+            }}
+        }}
+    "#,
+                user_id
+            ),
+            &admin_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let me = citizen_status(&shared_app, &token).await?;
+    assert_eq!(me["citizenship_status"], json!("ACTIVE"));
+    assert_eq!(me["citizen"]["status_reason"], json!(null));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_reinstate_active_citizen() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (user_id, _) = shared_app.create_citizen().await?;
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            reinstate_citizen(user_id: "{}") {{
+                status
+            }}
+        }}
+    "#,
+                user_id
+            ),
+            &admin_token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_STATUS_TRANSITION")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_admins_can_suspend_citizens() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (user_id, token) = shared_app.create_citizen().await?;
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            suspend_citizen(user_id: "{}", reason: "Suspending myself") {{
+                status
+            }}
+        }}
+    "#,
+                user_id
+            ),
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("FORBIDDEN")
+    );
+
+    Ok(())
+}
@@ -33,6 +33,7 @@ async fn can_create_ctiizenship_application() -> Result<(), anyhow::Error> {
     let shared_app = shared::SharedApp::init().await;
 
     let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
     let token = shared_app.login_specific(&email).await?;
 
     let response = shared_app
@@ -61,3 +62,168 @@ async fn can_create_ctiizenship_application() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn can_submit_typed_application() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            submit_application(application: {
+                citizenship: {
+                    date_of_birth: "1990-01-01T00:00:00Z",
+                    sex: "FEMALE",
+                    first_name: "Jane",
+                    last_name: "Doe",
+                    skills: [],
+                    occupations: [],
+                    country_of_citizenship: ["AU"],
+                    country_of_birth: "AU",
+                    country_of_residence: "AU",
+                    ethnic_groups: [],
+                }
+            })
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    let application_id = response["data"]["submit_application"].clone();
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                applications {
+                    id
+                    application {
+                        __typename
+                        ... on CitizenshipApplication {
+                            first_name
+                            citizenship_status
+                        }
+                    }
+                }
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let application = &response["data"]["me"]["applications"][0];
+    assert_eq!(application["id"], application_id);
+    assert_eq!(
+        application["application"]["__typename"],
+        json!("CitizenshipApplication")
+    );
+    assert_eq!(application["application"]["first_name"], json!("Jane"));
+    assert_eq!(
+        application["application"]["citizenship_status"],
+        json!("PENDING")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_application_fails() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            submit_application(application: {
+                citizenship: {
+                    date_of_birth: "1990-01-01T00:00:00Z",
+                    sex: "FEMALE",
+                    first_name: "",
+                    last_name: "Doe",
+                    skills: [],
+                    occupations: [],
+                    country_of_citizenship: ["AU"],
+                    country_of_birth: "AU",
+                    country_of_residence: "AU",
+                    ethnic_groups: [],
+                }
+            })
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_APPLICATION")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn applications_are_deleted_with_their_user() -> Result<(), anyhow::Error> {
+    use graph_api::schema::{applications, users};
+    use sea_orm::{ColumnTrait, Database, EntityTrait, PaginatorTrait, QueryFilter};
+
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            create_citizenship_application (
+                date_of_birth: 1,
+                sex: "MALE",
+                first_name: "John",
+                last_name: "Doe",
+                skills: [],
+                occupations: [],
+                country_of_citizenship: ["country1"],
+                country_of_birth: "country",
+                country_of_residence: "country",
+                ethnic_groups: [],
+            )
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let db = Database::connect(&shared_app.get_db_url()).await?;
+    let user = users::Entity::find()
+        .filter(users::Column::Email.eq(&email))
+        .one(&db)
+        .await?
+        .unwrap();
+
+    let application = applications::Entity::find().one(&db).await?.unwrap();
+    assert_eq!(application.user_id, user.id);
+
+    users::Entity::delete_by_id(user.id).exec(&db).await?;
+
+    assert_eq!(applications::Entity::find().count(&db).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,314 @@
+use serde_json::{json, Value};
+use shared::SharedApp;
+
+mod shared;
+
+async fn issue_credential(
+    shared_app: &SharedApp,
+    token: &Option<String>,
+) -> Result<Value, anyhow::Error> {
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            issue_citizenship_credential {
+                id
+                credential
+            }
+        }
+    "#,
+            token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    Ok(response["data"]["issue_citizenship_credential"].clone())
+}
+
+/// Verifies the credential without logging in, like a third party would
+async fn verify_credential(
+    shared_app: &SharedApp,
+    credential: &str,
+) -> Result<Value, anyhow::Error> {
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        query {{
+            verify_citizenship_credential(credential: "{}") {{
+                status
+                citizenship_number
+                issued_at
+            }}
+        }}
+    "#,
+                credential
+            ),
+            &None,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    Ok(response["data"]["verify_citizenship_credential"].clone())
+}
+
+#[tokio::test]
+async fn citizen_can_issue_verifiable_credential() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (_, token) = shared_app.create_citizen().await?;
+    let issued = issue_credential(&shared_app, &token).await?;
+
+    let verification =
+        verify_credential(&shared_app, issued["credential"].as_str().unwrap()).await?;
+
+    assert_eq!(verification["status"], json!("VALID"));
+    assert_eq!(verification["citizenship_number"], json!("L000000018"));
+    assert_ne!(verification["issued_at"], json!(null));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn credentials_are_verified_with_the_published_key() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (_, token) = shared_app.create_citizen().await?;
+    let issued = issue_credential(&shared_app, &token).await?;
+    let credential = issued["credential"].as_str().unwrap();
+
+    let response = shared_app.get("/.well-known/jwks.json").await?;
+    assert_eq!(response.status(), 200);
+    let jwks: jsonwebtoken::jwk::JwkSet = serde_json::from_slice(response.body())?;
+
+    let kid = jsonwebtoken::decode_header(credential)?.kid.unwrap();
+    let jwk = jwks.find(&kid).unwrap();
+    let jsonwebtoken::jwk::AlgorithmParameters::OctetKeyPair(parameters) = &jwk.algorithm else {
+        panic!("expected an Ed25519 key");
+    };
+
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::EdDSA);
+    validation.set_issuer(&["https://lumina.earth"]);
+    let claims = jsonwebtoken::decode::<Value>(
+        credential,
+        &jsonwebtoken::DecodingKey::from_ed_components(&parameters.x)?,
+        &validation,
+    )?
+    .claims;
+
+    assert_eq!(claims["sub"], json!("L000000018"));
+    assert_eq!(claims["jti"], issued["id"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn revoked_credentials_are_not_valid() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (_, token) = shared_app.create_citizen().await?;
+    let issued = issue_credential(&shared_app, &token).await?;
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            revoke_citizenship_credential(credential_id: "{}", reason: "Lost my phone") {{
+                revoked_at
+            }}
+        }}
+    "#,
+                issued["id"].as_str().unwrap()
+            ),
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let verification =
+        verify_credential(&shared_app, issued["credential"].as_str().unwrap()).await?;
+    assert_eq!(verification["status"], json!("REVOKED"));
+    assert_eq!(verification["citizenship_number"], json!(null));
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            revoked_citizenship_credentials {
+                id
+            }
+        }
+    "#,
+            &None,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["revoked_citizenship_credentials"],
+        json!([{ "id": issued["id"] }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn suspending_citizen_revokes_credentials() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (user_id, token) = shared_app.create_citizen().await?;
+    let issued = issue_credential(&shared_app, &token).await?;
+
+    let admin_email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&admin_email, "admin").await?;
+    let admin_token = shared_app.login_specific(&admin_email).await?;
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            suspend_citizen(user_id: "{}", reason: "Under investigation") {{
+                status
+            }}
+        }}
+    "#,
+                user_id
+            ),
+            &admin_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    let verification =
+        verify_credential(&shared_app, issued["credential"].as_str().unwrap()).await?;
+    assert_eq!(verification["status"], json!("REVOKED"));
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            issue_citizenship_credential {
+                id
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("CITIZENSHIP_SUSPENDED")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn renouncing_citizenship_revokes_credentials() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let (_, token) = shared_app.create_citizen().await?;
+    let issued = issue_credential(&shared_app, &token).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            renounce_citizenship(reason: "Moving on") {
+                status
+                status_reason
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["renounce_citizenship"],
+        json!({ "status": "RENOUNCED", "status_reason": "Moving on" })
+    );
+
+    let verification =
+        verify_credential(&shared_app, issued["credential"].as_str().unwrap()).await?;
+    assert_eq!(verification["status"], json!("REVOKED"));
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            renounce_citizenship {
+                status
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("INVALID_STATUS_TRANSITION")
+    );
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            issue_citizenship_credential {
+                id
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("NOT_A_CITIZEN")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn auth_tokens_are_not_credentials() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let verification = verify_credential(&shared_app, token.as_deref().unwrap()).await?;
+    assert_eq!(verification["status"], json!("INVALID"));
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            issue_citizenship_credential {
+                id
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("NOT_A_CITIZEN")
+    );
+
+    Ok(())
+}
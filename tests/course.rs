@@ -0,0 +1,415 @@
+use serde_json::{json, Value};
+use shared::SharedApp;
+
+mod shared;
+
+async fn login_as_admin(shared_app: &SharedApp) -> Result<Option<String>, anyhow::Error> {
+    let email = shared_app
+        .create_user_with_email("admin@lumina.earth")
+        .await?;
+    shared_app.set_role(&email, "admin").await?;
+
+    shared_app.login_specific(&email).await
+}
+
+async fn list_courses(
+    shared_app: &SharedApp,
+    token: &Option<String>,
+) -> Result<Value, anyhow::Error> {
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            courses {
+                slug
+                published
+                prerequisites {
+                    slug
+                }
+                units {
+                    slug
+                    questions {
+                        slug
+                        question
+                    }
+                }
+            }
+        }
+    "#,
+            token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    Ok(response["data"]["courses"].clone())
+}
+
+#[tokio::test]
+async fn admin_can_build_and_publish_courses() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            intro: save_course(slug: "intro", title: "Introduction") { slug }
+            governance: save_course(slug: "governance", title: "Governance", position: 1, prerequisites: ["intro"]) { slug }
+            second: save_course_unit(course_slug: "intro", slug: "second", title: "Second", position: 2) { slug }
+            first: save_course_unit(course_slug: "intro", slug: "first", title: "First", position: 1) { slug }
+            question: save_unit_question(course_slug: "intro", unit_slug: "first", slug: "why", question: "Why Lumina?") { slug }
+            publish: publish_course(slug: "intro") { published }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+
+    // governance is still unpublished, so only admins can see it
+    assert_eq!(
+        list_courses(&shared_app, &None).await?,
+        json!([{
+            "slug": "intro",
+            "published": true,
+            "prerequisites": [],
+            "units": [
+                { "slug": "first", "questions": [{ "slug": "why", "question": "Why Lumina?" }] },
+                { "slug": "second", "questions": [] },
+            ],
+        }])
+    );
+
+    let courses = list_courses(&shared_app, &admin_token).await?;
+    assert_eq!(courses[1]["slug"], "governance");
+    assert_eq!(courses[1]["published"], false);
+    assert_eq!(courses[1]["prerequisites"], json!([{ "slug": "intro" }]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_catalog_changes() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query(
+            r#"mutation { save_course(slug: "intro", title: "Introduction") { slug } }"#,
+            &token,
+        )
+        .await?;
+    assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"mutation { save_course(slug: "Intro Course", title: "Introduction") { slug } }"#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"][0]["extensions"]["code"], "INVALID_SLUG");
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            a: save_course(slug: "a", title: "A") { slug }
+            b: save_course(slug: "b", title: "B", prerequisites: ["a"]) { slug }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(
+            r#"mutation { save_course(slug: "a", title: "A", prerequisites: ["b"]) { slug } }"#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_PREREQUISITES"
+    );
+
+    let response = shared_app
+        .query(
+            r#"mutation { save_course_unit(course_slug: "missing", slug: "one", title: "One") { slug } }"#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "COURSE_NOT_FOUND"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn units_cannot_be_completed_before_prerequisites() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app
+        .create_course("intro", &["first", "second"], &["why"])
+        .await?;
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            set_unit_prerequisites(course_slug: "intro", unit_slug: "second", prerequisites: [
+                { rule: PREVIOUS_UNITS_COMPLETED },
+                { rule: QUESTIONS_PASSED },
+            ]) {
+                prerequisites {
+                    rule
+                }
+            }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["set_unit_prerequisites"]["prerequisites"],
+        json!([{ "rule": "PREVIOUS_UNITS_COMPLETED" }, { "rule": "QUESTIONS_PASSED" }])
+    );
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+    let set_progress = |unit_slug: &str, status: &str| {
+        format!(
+            r#"mutation {{ set_unit_progress(course_slug: "intro", unit_slug: "{}", status: {}) {{ status }} }}"#,
+            unit_slug, status
+        )
+    };
+
+    // starting a unit is always allowed
+    let response = shared_app
+        .query(&set_progress("second", "IN_PROGRESS"), &token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(&set_progress("second", "COMPLETED"), &token)
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "PREREQUISITES_NOT_MET"
+    );
+    assert_eq!(
+        response["errors"][0]["extensions"]["missing"],
+        json!([
+            {
+                "rule": "PREVIOUS_UNITS_COMPLETED",
+                "unit_slug": "first",
+                "question_slug": null,
+                "course_slug": null,
+                "message": "Complete the unit \"first\" first",
+            },
+            {
+                "rule": "QUESTIONS_PASSED",
+                "unit_slug": null,
+                "question_slug": "why",
+                "course_slug": null,
+                "message": "Pass the question \"why\" first",
+            },
+        ])
+    );
+
+    let response = shared_app
+        .query(&set_progress("first", "COMPLETED"), &token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(&set_progress("second", "COMPLETED"), &token)
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["missing"][0]["question_slug"],
+        "why"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn courses_cannot_be_completed_before_prerequisite_courses() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app.create_course("intro", &["first"], &[]).await?;
+    shared_app.create_course("advanced", &["only"], &[]).await?;
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"mutation { save_course(slug: "advanced", title: "Advanced", prerequisites: ["intro"]) { slug } }"#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+    let complete = |course_slug: &str, unit_slug: &str| {
+        format!(
+            r#"mutation {{ set_unit_progress(course_slug: "{}", unit_slug: "{}", status: COMPLETED) {{ status }} }}"#,
+            course_slug, unit_slug
+        )
+    };
+
+    let response = shared_app
+        .query(&complete("advanced", "only"), &token)
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "PREREQUISITES_NOT_MET"
+    );
+    assert_eq!(
+        response["errors"][0]["extensions"]["missing"],
+        json!([{
+            "rule": "COURSE_COMPLETED",
+            "unit_slug": null,
+            "question_slug": null,
+            "course_slug": "intro",
+            "message": "Complete the course \"intro\" first",
+        }])
+    );
+
+    let response = shared_app
+        .query(&complete("intro", "first"), &token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(&complete("advanced", "only"), &token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    // units can't be given course prerequisites themselves
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            set_unit_prerequisites(course_slug: "advanced", unit_slug: "only", prerequisites: [
+                { rule: COURSE_COMPLETED },
+            ]) {
+                slug
+            }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_PREREQUISITES"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_circular_unit_prerequisites() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app
+        .create_course("intro", &["first", "second"], &[])
+        .await?;
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            set_unit_prerequisites(course_slug: "intro", unit_slug: "second", prerequisites: [
+                { rule: UNIT_COMPLETED, unit_slug: "first" },
+            ]) {
+                slug
+            }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            set_unit_prerequisites(course_slug: "intro", unit_slug: "first", prerequisites: [
+                { rule: UNIT_COMPLETED, unit_slug: "second" },
+            ]) {
+                slug
+            }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_PREREQUISITES"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn previous_units_count_towards_circular_prerequisites() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app
+        .create_course("intro", &["first", "second"], &[])
+        .await?;
+    let admin_token = login_as_admin(&shared_app).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            set_unit_prerequisites(course_slug: "intro", unit_slug: "second", prerequisites: [
+                { rule: PREVIOUS_UNITS_COMPLETED },
+            ]) {
+                slug
+            }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query(
+            r#"
+        mutation {
+            set_unit_prerequisites(course_slug: "intro", unit_slug: "first", prerequisites: [
+                { rule: UNIT_COMPLETED, unit_slug: "second" },
+            ]) {
+                slug
+            }
+        }
+    "#,
+            &admin_token,
+        )
+        .await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "INVALID_PREREQUISITES"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use base64::Engine;
+use serde_json::json;
+
+mod shared;
+
+const SET_UNIT_PROGRESS: &str = r#"
+    mutation($unit_slug: String!, $status: UnitStatus!) {
+        set_unit_progress(course_slug: "intro", unit_slug: $unit_slug, status: $status) {
+            status
+        }
+    }
+"#;
+
+const COURSE_CERTIFICATES: &str = r#"
+    query {
+        course_certificates {
+            id
+            course_slug
+            learner_name
+            course_title
+        }
+    }
+"#;
+
+#[tokio::test]
+async fn completing_a_course_issues_a_certificate() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app
+        .create_course("intro", &["first", "second"], &[])
+        .await?;
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    for (unit_slug, status) in [("first", "COMPLETED"), ("second", "IN_PROGRESS")] {
+        let response = shared_app
+            .query_with_variables(
+                SET_UNIT_PROGRESS,
+                json!({ "unit_slug": unit_slug, "status": status }),
+                &token,
+            )
+            .await?;
+        assert_eq!(response["errors"], json!(null));
+    }
+
+    let response = shared_app.query(COURSE_CERTIFICATES, &token).await?;
+    assert_eq!(response["data"]["course_certificates"], json!([]));
+
+    // completing the course again doesn't issue a second certificate
+    for _ in 0..2 {
+        let response = shared_app
+            .query_with_variables(
+                SET_UNIT_PROGRESS,
+                json!({ "unit_slug": "second", "status": "COMPLETED" }),
+                &token,
+            )
+            .await?;
+        assert_eq!(response["errors"], json!(null));
+    }
+
+    let response = shared_app.query(COURSE_CERTIFICATES, &token).await?;
+    assert_eq!(response["errors"], json!(null));
+    let certificates = response["data"]["course_certificates"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(certificates.len(), 1);
+    assert_eq!(certificates[0]["course_slug"], "intro");
+    assert_eq!(certificates[0]["learner_name"], "John Doe");
+    assert_eq!(certificates[0]["course_title"], "intro");
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            course_completion_stats(granularity: DAY, count: 2) {
+                new
+                cumulative
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["course_completion_stats"],
+        json!([{ "new": 0, "cumulative": 0 }, { "new": 1, "cumulative": 1 }])
+    );
+
+    // anyone can verify the certificate and download it
+    let response = shared_app
+        .query_with_variables(
+            r#"
+        query($id: UUID!) {
+            verify_certificate(id: $id) {
+                learner_name
+                course_title
+                completed_at
+                pdf
+                pdf_path
+            }
+        }
+    "#,
+            json!({ "id": certificates[0]["id"] }),
+            &None,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    let certificate = &response["data"]["verify_certificate"];
+    assert_eq!(certificate["learner_name"], "John Doe");
+    assert_ne!(certificate["completed_at"], json!(null));
+
+    let pdf = base64::engine::general_purpose::STANDARD
+        .decode(certificate["pdf"].as_str().unwrap_or_default())?;
+    assert!(pdf.starts_with(b"%PDF-"));
+
+    let response = shared_app
+        .get(certificate["pdf_path"].as_str().unwrap_or_default())
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/pdf");
+    assert_eq!(response.body().as_ref(), pdf.as_slice());
+
+    let response = shared_app
+        .get("/certificates/00000000-0000-0000-0000-000000000000.pdf")
+        .await?;
+    assert_eq!(response.status(), 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_certificates_are_not_verified() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            verify_certificate(id: "00000000-0000-0000-0000-000000000000") {
+                learner_name
+            }
+        }
+    "#,
+            &None,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(response["data"]["verify_certificate"], json!(null));
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use serde_json::json;
+use shared::SharedApp;
+
+mod shared;
+
+async fn create_citizenship_application(
+    date_of_birth: &str,
+    token: &Option<String>,
+    shared_app: &SharedApp,
+) -> Result<serde_json::Value, anyhow::Error> {
+    shared_app
+        .query(
+            &format!(
+                r#"
+        mutation {{
+            submit_application(application: {{
+                citizenship: {{
+                    date_of_birth: "{}",
+                    sex: "FEMALE",
+                    first_name: "Jane",
+                    last_name: "Doe",
+                    skills: [],
+                    occupations: [],
+                    country_of_citizenship: ["AU"],
+                    country_of_birth: "AU",
+                    country_of_residence: "AU",
+                    ethnic_groups: [],
+                }}
+            }})
+        }}
+    "#,
+                date_of_birth
+            ),
+            token,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn unverified_email_is_not_eligible() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response =
+        create_citizenship_application("1990-01-01T00:00:00Z", &token, &shared_app).await?;
+
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("NOT_ELIGIBLE")
+    );
+    assert_eq!(
+        response["errors"][0]["extensions"]["failures"][0]["rule"],
+        json!("EMAIL_VERIFIED")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_one_open_application() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response =
+        create_citizenship_application("1990-01-01T00:00:00Z", &token, &shared_app).await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response =
+        create_citizenship_application("1990-01-01T00:00:00Z", &token, &shared_app).await?;
+    assert_eq!(
+        response["errors"][0]["extensions"]["failures"][0]["rule"],
+        json!("NO_OPEN_APPLICATION")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn concurrent_applications_are_not_both_accepted() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let (first, second) = tokio::join!(
+        create_citizenship_application("1990-01-01T00:00:00Z", &token, &shared_app),
+        create_citizenship_application("1990-01-01T00:00:00Z", &token, &shared_app),
+    );
+    let responses = [first?, second?];
+
+    let accepted = responses
+        .iter()
+        .filter(|response| response["errors"] == json!(null))
+        .count();
+    assert_eq!(accepted, 1);
+    for response in responses
+        .iter()
+        .filter(|response| response["errors"] != json!(null))
+    {
+        assert_eq!(
+            response["errors"][0]["extensions"]["failures"][0]["rule"],
+            json!("NO_OPEN_APPLICATION")
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_check_eligibility() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    shared_app.verify_email(&email).await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            citizenship_eligibility(application: {
+                date_of_birth: "2020-01-01T00:00:00Z",
+                sex: "FEMALE",
+                first_name: "Jane",
+                last_name: "Doe",
+                skills: [],
+                occupations: [],
+                country_of_citizenship: ["AU"],
+                country_of_birth: "AU",
+                country_of_residence: "AU",
+                ethnic_groups: [],
+            }) {
+                rule
+                message
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(
+        response["data"]["citizenship_eligibility"],
+        json!([{
+            "rule": "MINIMUM_AGE",
+            "message": "You must be at least 18 years old to apply"
+        }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_verify_email() -> Result<(), anyhow::Error> {
+    use sea_orm::{Database, EntityTrait};
+
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query("mutation { send_email_verification }", &token)
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let db = Database::connect(&shared_app.get_db_url()).await?;
+    let verification_token = graph_api::schema::email_verification_tokens::Entity::find()
+        .one(&db)
+        .await?
+        .expect("should create a token");
+
+    let response = shared_app
+        .query(
+            &format!(
+                r#"mutation {{ verify_email(token_id: "{}") }}"#,
+                verification_token.id
+            ),
+            &None,
+        )
+        .await?;
+    assert_eq!(response["errors"], json!(null));
+
+    let response = shared_app
+        .query("query { me { email_verified_at } }", &token)
+        .await?;
+    assert!(response["data"]["me"]["email_verified_at"].is_string());
+
+    Ok(())
+}
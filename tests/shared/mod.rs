@@ -5,7 +5,7 @@ use crate::shared::custom_postgres::Postgres;
 use graph_api::{App, SECRET_VARIABLES};
 use lambda_http::Body;
 use lazy_static::lazy_static;
-use sea_orm::{ConnectionTrait, Database};
+use sea_orm::{sea_query::Expr, ColumnTrait, ConnectionTrait, Database, EntityTrait, QueryFilter};
 use serde_json::{json, Value};
 use testcontainers::clients::Cli;
 use testcontainers::Container;
@@ -30,6 +30,13 @@ impl SharedApp {
         );
 
         std::env::set_var("TEST_POSTGRES_URL", &postgres_url);
+        // grade answers with fixed rules instead of calling OpenAI
+        std::env::set_var("ASSESSMENT_PROVIDER", "mock");
+        // only used by the tests
+        std::env::set_var(
+            "CREDENTIAL_SIGNING_KEY",
+            "MC4CAQAwBQYDK2VwBCIEIN1ba3uXhhXEMYvtJ/90LYCGqPkDHSBK6/vhLPkMy5jD",
+        );
 
         {
             let db = Database::connect(&postgres_url).await.unwrap();
@@ -61,8 +68,126 @@ impl SharedApp {
         })
         .to_string();
 
+        self.send(lambda_http::Request::new(Body::from(req_body)), token)
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn query_with_variables(
+        &self,
+        query: &str,
+        variables: Value,
+        token: &Option<String>,
+    ) -> Result<Value, anyhow::Error> {
+        let req_body = json!({
+            "query": query,
+            "variables": variables,
+        })
+        .to_string();
+
+        self.send(lambda_http::Request::new(Body::from(req_body)), token)
+            .await
+    }
+
+    /// Sends a GraphQL multipart request, with the file bound to the `$file` variable
+    #[allow(dead_code)]
+    pub async fn upload(
+        &self,
+        query: &str,
+        variables: Value,
+        file_name: &str,
+        content_type: &str,
+        content: &[u8],
+        token: &Option<String>,
+    ) -> Result<Value, anyhow::Error> {
+        let boundary = "graph-api-test-boundary";
+
+        let mut variables = variables;
+        variables["file"] = Value::Null;
+        let operations = json!({
+            "query": query,
+            "variables": variables,
+        });
+
+        let mut req_body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"operations\"\r\n\r\n{operations}\r\n\
+            --{boundary}\r\nContent-Disposition: form-data; name=\"map\"\r\n\r\n{{\"0\": [\"variables.file\"]}}\r\n\
+            --{boundary}\r\nContent-Disposition: form-data; name=\"0\"; filename=\"{file_name}\"\r\n\
+            Content-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        req_body.extend_from_slice(content);
+        req_body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
         let mut request = lambda_http::Request::new(Body::from(req_body));
+        request.headers_mut().append(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", boundary)
+                .parse()
+                .unwrap(),
+        );
 
+        self.send(request, token).await
+    }
+
+    /// Runs an operation over server-sent events, returning the data of
+    /// each `next` event
+    #[allow(dead_code)]
+    pub async fn subscribe(
+        &self,
+        query: &str,
+        variables: Value,
+        token: &Option<String>,
+    ) -> Result<Vec<Value>, anyhow::Error> {
+        let req_body = json!({
+            "query": query,
+            "variables": variables,
+        })
+        .to_string();
+
+        let mut request = lambda_http::Request::new(Body::from(req_body));
+        request
+            .headers_mut()
+            .append("Accept", "text/event-stream".parse().unwrap());
+
+        let res = self.post(request, token).await?;
+        let body = std::str::from_utf8(res.body())?;
+
+        body.split("\n\n")
+            .filter_map(|event| event.strip_prefix("event: next\ndata: "))
+            .map(|data| Ok(serde_json::from_str(data)?))
+            .collect()
+    }
+
+    /// Fetches one of the files served outside of GraphQL
+    #[allow(dead_code)]
+    pub async fn get(&self, path: &str) -> Result<lambda_http::Response<Body>, anyhow::Error> {
+        let mut request = lambda_http::Request::new(Body::Empty);
+        *request.uri_mut() = path.parse()?;
+
+        self.app
+            .respond(request)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    async fn send(
+        &self,
+        request: lambda_http::Request,
+        token: &Option<String>,
+    ) -> Result<Value, anyhow::Error> {
+        let res = self.post(request, token).await?;
+
+        let body = res.body();
+
+        Ok(serde_json::from_slice(body)?)
+    }
+
+    async fn post(
+        &self,
+        mut request: lambda_http::Request,
+        token: &Option<String>,
+    ) -> Result<lambda_http::Response<Body>, anyhow::Error> {
         *request.method_mut() = lambda_http::http::Method::POST;
         match token {
             Some(token) => {
@@ -74,15 +199,10 @@ impl SharedApp {
             None => {}
         }
 
-        let res = self
-            .app
+        self.app
             .respond(request)
             .await
-            .map_err(|e| anyhow::anyhow!(e))?;
-
-        let body = res.body();
-
-        Ok(serde_json::from_slice(body)?)
+            .map_err(|e| anyhow::anyhow!(e))
     }
 
     #[allow(dead_code)]
@@ -151,7 +271,11 @@ impl SharedApp {
 
     #[allow(dead_code)]
     pub async fn create_user(&self) -> Result<String, anyhow::Error> {
-        let user_email: String = "gov@lumina.earth".to_owned();
+        self.create_user_with_email("gov@lumina.earth").await
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_user_with_email(&self, user_email: &str) -> Result<String, anyhow::Error> {
         let res = self
             .query(
                 &format!(
@@ -161,9 +285,9 @@ impl SharedApp {
                     password: \"password\"
                     first_name: \"John\",
                     last_name: \"Doe\",
-                    calling_code: \"AU\"
-                    country_code: \"61\",
-                    phone_number: \"000\",
+                    calling_code: \"61\"
+                    country_code: \"AU\",
+                    phone_number: \"0412345678\",
                     referrer: null
                 )
             }}",
@@ -174,28 +298,182 @@ impl SharedApp {
             .await?;
         assert_eq!(res["errors"], json!(null));
 
-        Ok(user_email)
+        Ok(user_email.to_string())
     }
 
+    /// Roles can't be assigned through the API, so they are set directly in the database
     #[allow(dead_code)]
-    pub async fn create_user_with_admin_role(&self) -> Result<String, anyhow::Error> {
-        let user_email = self.create_user().await?;
+    pub async fn set_role(&self, email: &str, role: &str) -> Result<(), anyhow::Error> {
+        let db = Database::connect(&self.get_db_url()).await?;
 
-        let res = self
+        graph_api::schema::users::Entity::update_many()
+            .col_expr(
+                graph_api::schema::users::Column::Role,
+                Expr::value(role.to_string()),
+            )
+            .filter(graph_api::schema::users::Column::Email.eq(email))
+            .exec(&db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks the user's email as verified without going through the email flow
+    #[allow(dead_code)]
+    pub async fn verify_email(&self, email: &str) -> Result<(), anyhow::Error> {
+        let db = Database::connect(&self.get_db_url()).await?;
+
+        graph_api::schema::users::Entity::update_many()
+            .col_expr(
+                graph_api::schema::users::Column::EmailVerifiedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(graph_api::schema::users::Column::Email.eq(email))
+            .exec(&db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Submits a citizenship application and returns its id
+    #[allow(dead_code)]
+    pub async fn create_citizenship_application(
+        &self,
+        token: &Option<String>,
+    ) -> Result<String, anyhow::Error> {
+        let response = self
             .query(
-                "
-        query {
-            me {
-                assign_role(role: \"admin\")
+                r#"
+            mutation {
+                create_citizenship_application (
+                    date_of_birth: 1,
+                    sex: "MALE",
+                    first_name: "John",
+                    last_name: "Doe",
+                    skills: ["skill1", "skill2"],
+                    occupations: ["occupation1", "occupation2"],
+                    country_of_citizenship: ["country1", "country2"],
+                    country_of_birth: "country",
+                    country_of_residence: "country",
+                    ethnic_groups: ["ethnic1", "ethnic2"],
+                )
             }
-        }
-    ",
-                &self.login_specific(&user_email).await?,
+        "#,
+                token,
             )
             .await?;
 
-        assert_eq!(res["errors"], json!(null));
+        assert_eq!(response["errors"], json!(null));
 
-        Ok(user_email.to_string())
+        Ok(response["data"]["create_citizenship_application"]
+            .as_str()
+            .unwrap()
+            .to_string())
+    }
+
+    /// Creates a user with an approved citizenship application, and returns
+    /// their user id and token
+    #[allow(dead_code)]
+    pub async fn create_citizen(&self) -> Result<(String, Option<String>), anyhow::Error> {
+        let email = self.create_user().await?;
+        self.verify_email(&email).await?;
+        let token = self.login_specific(&email).await?;
+        let application_id = self.create_citizenship_application(&token).await?;
+
+        let reviewer_email = self.create_user_with_email("reviewer@lumina.earth").await?;
+        self.set_role(&reviewer_email, "reviewer").await?;
+        let reviewer_token = self.login_specific(&reviewer_email).await?;
+
+        let response = self
+            .query(
+                &format!(
+                    r#"
+            mutation {{
+                approve_citizenship_application(application_id: "{}") {{
+                    user_id
+                }}
+            }}
+        "#,
+                    application_id
+                ),
+                &reviewer_token,
+            )
+            .await?;
+
+        assert_eq!(response["errors"], json!(null));
+
+        let user_id = response["data"]["approve_citizenship_application"]["user_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        Ok((user_id, token))
+    }
+
+    /// Adds a published course with these units to the catalog, each unit
+    /// with the given questions
+    #[allow(dead_code)]
+    pub async fn create_course(
+        &self,
+        course_slug: &str,
+        unit_slugs: &[&str],
+        question_slugs: &[&str],
+    ) -> Result<(), anyhow::Error> {
+        use graph_api::schema::{course_units, courses, unit_questions};
+        use sea_orm::{ActiveModelTrait, IntoActiveModel};
+
+        let db = Database::connect(&self.get_db_url()).await?;
+
+        courses::Model {
+            slug: course_slug.to_string(),
+            title: course_slug.to_string(),
+            description: None,
+            position: 0,
+            published_at: Some(chrono::Utc::now()),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+        .into_active_model()
+        .insert(&db)
+        .await?;
+
+        for (position, unit_slug) in unit_slugs.iter().enumerate() {
+            course_units::Model {
+                course_slug: course_slug.to_string(),
+                slug: unit_slug.to_string(),
+                title: unit_slug.to_string(),
+                description: None,
+                position: position as i32,
+            }
+            .into_active_model()
+            .insert(&db)
+            .await?;
+
+            for (position, question_slug) in question_slugs.iter().enumerate() {
+                unit_questions::Model {
+                    course_slug: course_slug.to_string(),
+                    unit_slug: unit_slug.to_string(),
+                    slug: question_slug.to_string(),
+                    question: format!("What is {}?", question_slug),
+                    context: None,
+                    reference_answer: None,
+                    rubric: None,
+                    position: position as i32,
+                }
+                .into_active_model()
+                .insert(&db)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_user_with_admin_role(&self) -> Result<String, anyhow::Error> {
+        let user_email = self.create_user().await?;
+        self.set_role(&user_email, "admin").await?;
+
+        Ok(user_email)
     }
 }
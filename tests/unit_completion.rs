@@ -58,6 +58,7 @@ async fn mark_unit_as_completed() -> Result<(), anyhow::Error> {
 
     let user_email = shared_app.create_user().await?;
     let token = shared_app.login_specific(&user_email).await?;
+    shared_app.create_course("foo", &["bar"], &[]).await?;
 
     let res_1 = set_unit_progress("foo", "bar", "IN_PROGRESS", &token, &shared_app).await?;
 
@@ -87,6 +88,7 @@ async fn can_get_course_progress() -> Result<(), anyhow::Error> {
 
     let user_email = shared_app.create_user().await?;
     let token = shared_app.login_specific(&user_email).await?;
+    shared_app.create_course("foo", &["bar"], &[]).await?;
 
     set_unit_progress("foo", "bar", "IN_PROGRESS", &token, &shared_app).await?;
 
@@ -121,6 +123,8 @@ async fn can_get_all_course_progress() -> Result<(), anyhow::Error> {
 
     let user_email = shared_app.create_user().await?;
     let token = shared_app.login_specific(&user_email).await?;
+    shared_app.create_course("foo", &["bar"], &[]).await?;
+    shared_app.create_course("xyz", &["bar"], &[]).await?;
 
     set_unit_progress("foo", "bar", "IN_PROGRESS", &token, &shared_app).await?;
     set_unit_progress("xyz", "bar", "IN_PROGRESS", &token, &shared_app).await?;
@@ -189,6 +193,9 @@ async fn can_get_last_updated_unit() -> Result<(), anyhow::Error> {
 
     let user_email = shared_app.create_user().await?;
     let token = shared_app.login_specific(&user_email).await?;
+    shared_app
+        .create_course("foo", &["bar", "xyz"], &[])
+        .await?;
 
     // User's last updated unit should be null if they haven't completed any units
     let res_1 = last_updated_unit(&token, &shared_app).await?;
@@ -219,6 +226,23 @@ async fn can_get_last_updated_unit() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn cannot_set_progress_for_unknown_units() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let user_email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&user_email).await?;
+    shared_app.create_course("foo", &["bar"], &[]).await?;
+
+    let res = set_unit_progress("nope", "bar", "COMPLETED", &token, &shared_app).await?;
+    assert_eq!(res["errors"][0]["extensions"]["code"], "COURSE_NOT_FOUND");
+
+    let res = set_unit_progress("foo", "nope", "COMPLETED", &token, &shared_app).await?;
+    assert_eq!(res["errors"][0]["extensions"]["code"], "UNIT_NOT_FOUND");
+
+    Ok(())
+}
+
 //testing if all course progress query sorts by updated_at
 #[tokio::test]
 async fn all_course_progress_sorts_by_updated_at() -> Result<(), anyhow::Error> {
@@ -226,6 +250,11 @@ async fn all_course_progress_sorts_by_updated_at() -> Result<(), anyhow::Error>
 
     let user_email = shared_app.create_user().await?;
     let token = shared_app.login_specific(&user_email).await?;
+    shared_app
+        .create_course("foo", &["0", "1", "2", "3"], &[])
+        .await?;
+    shared_app.create_course("xyz", &["bar"], &[]).await?;
+    shared_app.create_course("abc", &["bar"], &[]).await?;
 
     set_unit_progress("foo", "3", "IN_PROGRESS", &token, &shared_app).await?;
     set_unit_progress("xyz", "bar", "NOT_STARTED", &token, &shared_app).await?;
@@ -317,3 +346,75 @@ async fn all_course_progress_sorts_by_updated_at() -> Result<(), anyhow::Error>
 
     Ok(())
 }
+
+#[tokio::test]
+async fn can_get_course_progress_summaries() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let user_email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&user_email).await?;
+    shared_app
+        .create_course("foo", &["0", "1", "2", "3"], &[])
+        .await?;
+    shared_app.create_course("xyz", &["bar"], &[]).await?;
+
+    set_unit_progress("foo", "0", "COMPLETED", &token, &shared_app).await?;
+    set_unit_progress("foo", "1", "IN_PROGRESS", &token, &shared_app).await?;
+    set_unit_progress("foo", "2", "COMPLETED", &token, &shared_app).await?;
+
+    let res = shared_app
+        .query(
+            r#"
+        query {
+            foo: course_progress_summary(course_slug: "foo") {
+                course_slug
+                total_units
+                completed_units
+                in_progress_units
+                percent_complete
+                next_unit_slug
+            }
+            xyz: course_progress_summary(course_slug: "xyz") {
+                percent_complete
+                last_activity_at
+                next_unit_slug
+            }
+            missing: course_progress_summary(course_slug: "missing") {
+                percent_complete
+            }
+            course_progress_summaries {
+                course_slug
+                last_activity_at
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(res["errors"], json!(null));
+    assert_eq!(
+        res["data"]["foo"],
+        json!({
+            "course_slug": "foo",
+            "total_units": 4,
+            "completed_units": 2,
+            "in_progress_units": 1,
+            "percent_complete": 50,
+            "next_unit_slug": "1",
+        })
+    );
+    assert_eq!(
+        res["data"]["xyz"],
+        json!({ "percent_complete": 0, "last_activity_at": null, "next_unit_slug": "bar" })
+    );
+    assert_eq!(res["data"]["missing"], json!(null));
+
+    // only started courses are summarised
+    let summaries = res["data"]["course_progress_summaries"].as_array().unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0]["course_slug"], "foo");
+    assert!(summaries[0]["last_activity_at"].is_string());
+
+    Ok(())
+}
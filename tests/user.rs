@@ -101,3 +101,67 @@ async fn duplicate_user_fails() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn phone_number_is_stored_as_e164() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let email = shared_app.create_user().await?;
+    let token = shared_app.login_specific(&email).await?;
+
+    let response = shared_app
+        .query(
+            r#"
+        query {
+            me {
+                calling_code
+                country_code
+                phone_number
+            }
+        }
+    "#,
+            &token,
+        )
+        .await?;
+
+    assert_eq!(response["errors"], json!(null));
+    assert_eq!(response["data"]["me"]["calling_code"], json!("61"));
+    assert_eq!(response["data"]["me"]["country_code"], json!("AU"));
+    assert_eq!(
+        response["data"]["me"]["phone_number"],
+        json!("+61412345678")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_phone_number_fails() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let res = shared_app
+        .query(
+            r#"
+            mutation {
+                create_user(
+                    email: "hello@123.com",
+                    password: "password",
+                    first_name: "John",
+                    last_name: "Doe",
+                    calling_code: "61",
+                    country_code: "AU",
+                    phone_number: "000"
+                )
+            }
+        "#,
+            &None,
+        )
+        .await?;
+
+    assert_eq!(
+        res["errors"][0]["extensions"]["code"],
+        json!("INVALID_PHONE_NUMBER")
+    );
+
+    Ok(())
+}
@@ -19,3 +19,57 @@ async fn test_user_count() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_user_growth() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    shared_app.create_user().await?;
+
+    let res = shared_app
+        .query(
+            "
+        query {
+            user_growth(granularity: MONTH, count: 3) {
+                bucket
+                new
+                cumulative
+            }
+        }
+    ",
+            &None,
+        )
+        .await?;
+
+    assert_eq!(res["errors"], serde_json::json!(null));
+
+    let buckets = res["data"]["user_growth"].as_array().unwrap();
+    assert_eq!(buckets.len(), 3);
+    assert_eq!(buckets[0]["cumulative"], 0);
+    assert_eq!(buckets[2]["new"], 1);
+    assert_eq!(buckets[2]["cumulative"], 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_user_growth_rejects_large_count() -> Result<(), anyhow::Error> {
+    let shared_app = shared::SharedApp::init().await;
+
+    let res = shared_app
+        .query(
+            "
+        query {
+            user_growth(granularity: DAY, count: 1000) {
+                bucket
+            }
+        }
+    ",
+            &None,
+        )
+        .await?;
+
+    assert_eq!(res["errors"][0]["extensions"]["code"], "BAD_REQUEST");
+
+    Ok(())
+}